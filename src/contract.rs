@@ -1,19 +1,113 @@
+use std::cmp::Ordering;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+    from_slice, to_binary, to_vec, Addr, Api, BankMsg, Binary, Coin, Deps, DepsMut, Empty, Env,
+    Event, MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, Timestamp,
+    Uint128,
 };
-use cw0::maybe_addr;
+use cw0::{maybe_addr, Duration, Expiration};
 use cw2::set_contract_version;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 
+use crate::drand::{derive_move, verify_beacon};
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, GamesListResponse, InstantiateMsg, QueryMsg};
-use crate::state::{Game, GameMove, GameResult, State, ADMIN, GAME, HOOKS, STATE};
+use crate::events::{
+    self, AdminActionEvent, GameCancelledEvent, GameKind, GameResolvedEvent, GameSponsoredEvent,
+    GameStartedEvent,
+};
+use crate::ibc::{
+    query_ibc_channels, try_ibc_cancel_challenge, try_ibc_challenge, try_ibc_claim_timeout,
+    try_ibc_respond, try_ibc_reveal_move,
+};
+use crate::msg::{
+    BotMoveResponse, BotQueryMsg, BotsResponse, ChannelMove, ChannelState, Cw20ExecuteMsg,
+    ExecuteMsg, GameCallbackMsg, GameFinishedHookMsg, GameOutcome, GamesListResponse,
+    GuildStandingsResponse, HeadToHeadResponse, HillResponse, HouseResponse, IbcChannelsResponse,
+    InstantiateMsg, OperatorsResponse, QueryMsg, QueueResponse, RelayNonceResponse, RelayedAction,
+    RelayedPayload, ResolveNicknameResponse, RewardAllowanceResponse, SignedChannelMove,
+    SignedChannelState, SponsoredGame, StreakEntry, StreaksResponse, TrophyKind, TrophyMetadata,
+    TrophyMintMsg,
+};
+use crate::relay::{payload_hash, pubkey_to_canonical};
+use crate::state::{
+    Channel, ChannelDispute, ChannelStatus, DailyRewards, EmissionRate, Game, GameCallback,
+    GameMove, GameRecord, GameResult, GlobalStats, Guild, GuildWar, GuildWarStatus, Hill,
+    HillChallenge, HillConfig, HouseConfig, HouseGame, OperatorGrant, PairActivity, Prize, Profile,
+    QueueEntry, QueuedGame, RankedConfig, RematchOffer, RewardConfig, Round, RoundPhase,
+    RoundPlayer, RoundResult, Season, SeasonScore, SidePool, SidePoolStatus, SideStakes,
+    Sponsorship, Standing, State, StreakConfig, StreakMilestone, ACTIVE_GUILD_WARS, ADMIN, BOTS,
+    BOT_GAME_SEQ, CHANNELS, CHANNEL_SEQ, CURRENT_SEASON, DAILY_REWARDS, GAME, GAME_RECORDS,
+    GAME_RECORD_SEQ, GLOBAL_STATS, GUILDS, GUILD_INVITES, GUILD_MEMBERS, GUILD_NAMES, GUILD_SEQ,
+    GUILD_WARS, GUILD_WAR_SEQ, HEAD_TO_HEAD, HILL, HILL_CONFIG, HOOKS, HOUSE, HOUSE_BANKROLL,
+    HOUSE_GAMES, HOUSE_GAME_SEQ, IBC_GAMES, NICKNAMES, OPERATORS, PAIR_ACTIVITY, PLAYER_STATS,
+    PROFILES, QUEUE, QUEUED_GAMES, QUEUED_GAME_SEQ, QUEUED_PLAYERS, QUEUE_BY_WAGER, QUEUE_SEQ,
+    RANKED_CONFIG, RELAY_NONCES, REMATCH_OFFERS, REWARD_CONFIG, ROUNDS, ROUND_SEQ, SEASONS,
+    SEASON_RANKS, SEASON_SCORES, SEASON_SEQ, SIDE_BETS, SIDE_BET_FEE_BPS, SIDE_POOLS, STATE,
+    STREAKS, STREAK_CONFIG, SUBSCRIBERS, SUBSCRIBER_REPLY_ON_ERROR, TREASURY,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:rps-dapp-v2";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// how long a player waits in the matchmaking queue before the entry expires
+const QUEUE_ENTRY_TIMEOUT: Duration = Duration::Time(60 * 60);
+// how many waiting entries of the same wager a join looks at
+const MAX_QUEUE_SCAN: usize = 10;
+// how many expired entries PurgeQueue removes by default and at most
+const DEFAULT_PURGE_LIMIT: u32 = 10;
+const MAX_PURGE_LIMIT: u32 = 30;
+// how far past the block time the beacon of a house game must be published at
+// least, block times lag behind and published beacons spread fast, and at most
+const HOUSE_ROUND_MARGIN: u64 = 60;
+const MAX_HOUSE_ROUND_DELAY: u64 = 60 * 60;
+// how long after its round anyone can submit the beacon of a house game
+const HOUSE_BEACON_TIMEOUT: u64 = 60 * 60;
+// how long the host of a bot game has to reveal once the bot answered
+// salts shorter than this could be brute forced from the public commitments
+const MIN_SALT_LENGTH: usize = 16;
+// how long both players of a queued game have to reveal their moves
+const REVEAL_TIMEOUT: Duration = Duration::Time(60 * 60);
+// how long the players of a multi-player round have for each commit or reveal phase
+const ROUND_PHASE_TIMEOUT: Duration = Duration::Time(60 * 60);
+// how long the champion has to answer a challenge before forfeiting the seat
+const HILL_DEFENSE_TIMEOUT: Duration = Duration::Time(60 * 60);
+
+// how long either side of a closing channel has to answer with a later state
+const CHANNEL_CHALLENGE_PERIOD: Duration = Duration::Time(60 * 60);
+// how long both players of a disputed channel round have to reveal their moves
+const CHANNEL_REVEAL_TIMEOUT: Duration = Duration::Time(60 * 60);
+
+// how many streaks GetStreaks returns by default and at most
+const DEFAULT_STREAKS_LIMIT: u32 = 10;
+// how many sponsorships a single game or round takes
+const MAX_SPONSORS: usize = 10;
+// how many of the best players a season archives
+const SEASON_ARCHIVE_SIZE: usize = 10;
+const MAX_STREAKS_LIMIT: u32 = 30;
+
+// bounds of the profile fields
+const NICKNAME_LENGTH: std::ops::RangeInclusive<usize> = 3..=20;
+const MAX_AVATAR_URI_LENGTH: usize = 256;
+const MAX_BIO_LENGTH: usize = 280;
+
+// bounds of guild names and sizes
+const GUILD_NAME_LENGTH: std::ops::RangeInclusive<usize> = 3..=32;
+const MAX_GUILD_MEMBERS: usize = 50;
+
+// reply id of game finished hooks sent in reply-on-error mode
+const SUBSCRIBER_REPLY_ID: u64 = 1;
+// reply id of per-game callbacks, which always report failures instead of reverting
+const CALLBACK_REPLY_ID: u64 = 2;
+// reply id of trophy mints, a failed mint is reported instead of reverting the game
+const TROPHY_REPLY_ID: u64 = 3;
+// reply id of reward mints, which do not revert the game either
+const REWARD_REPLY_ID: u64 = 4;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     mut deps: DepsMut,
@@ -24,6 +118,7 @@ pub fn instantiate(
     let state = State {
         owner: info.sender.clone(),
         admin: msg.admin,
+        trophy_contract: None,
     };
 
     let api = deps.api;
@@ -45,674 +140,8667 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     let api = deps.api;
 
     match msg {
-        ExecuteMsg::UpdateAdmin { admin } => Ok(ADMIN.execute_update_admin(
-            deps,
-            info,
-            maybe_addr(api, Some(admin.to_string()))?,
-        )?),
+        ExecuteMsg::UpdateAdmin { admin } => {
+            let event = admin_event("update_admin", &info.sender, Some(admin.to_string()));
+            Ok(ADMIN
+                .execute_update_admin(deps, info, maybe_addr(api, Some(admin.to_string()))?)?
+                .add_event(event))
+        }
         ExecuteMsg::StartGame {
             opponent,
             host_move,
-        } => try_start_game(deps, info, opponent, host_move),
-        ExecuteMsg::AddToBlacklist { address } => Ok(HOOKS.execute_add_hook(
-            &ADMIN,
+            callback,
+            host,
+        } => try_start_game(deps, env, info, host, opponent, host_move, callback),
+        ExecuteMsg::CancelGame { opponent } => try_cancel_game(deps, info, opponent),
+        ExecuteMsg::AddToBlacklist { address } => {
+            let event = admin_event("add_to_blacklist", &info.sender, Some(address.to_string()));
+            Ok(HOOKS
+                .execute_add_hook(&ADMIN, deps, info, api.addr_validate(address.as_str())?)?
+                .add_event(event))
+        }
+        ExecuteMsg::RemoveFromBlacklist { address } => {
+            let event = admin_event(
+                "remove_from_blacklist",
+                &info.sender,
+                Some(address.to_string()),
+            );
+            Ok(HOOKS
+                .execute_remove_hook(&ADMIN, deps, info, api.addr_validate(address.as_str())?)?
+                .add_event(event))
+        }
+        ExecuteMsg::OpponentResponse {
+            host,
+            opponent,
+            opp_move,
+        } => try_opponent_response(deps, env, info, host, opponent, opp_move),
+        ExecuteMsg::JoinQueue {
+            wager,
+            move_commitment,
+            player,
+        } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_join_queue(deps, env, info, wager, move_commitment)
+        }
+        ExecuteMsg::LeaveQueue { player } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_leave_queue(deps, info)
+        }
+        ExecuteMsg::PurgeQueue { limit } => try_purge_queue(deps, env, limit),
+        ExecuteMsg::RevealMove {
+            game_id,
+            player_move,
+            salt,
+            player,
+        } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_reveal_move(deps, env, info, game_id, player_move, salt)
+        }
+        ExecuteMsg::ClaimTimeout { game_id } => try_claim_timeout(deps, env, game_id),
+        ExecuteMsg::PlaceSideBet { game_id, outcome } => {
+            try_place_side_bet(deps, info, game_id, outcome)
+        }
+        ExecuteMsg::SponsorGame { game } => try_sponsor_game(deps, info, game),
+        ExecuteMsg::ClaimSideBet { game_id } => try_claim_side_bet(deps, info, game_id),
+        ExecuteMsg::UpdateSideBetFee { fee_bps } => try_update_side_bet_fee(deps, info, fee_bps),
+        ExecuteMsg::UpdateHouseConfig {
+            drand_pubkey,
+            drand_genesis,
+            drand_period,
+        } => try_update_house_config(deps, info, drand_pubkey, drand_genesis, drand_period),
+        ExecuteMsg::FundHouse {} => try_fund_house(deps, info),
+        ExecuteMsg::WithdrawHouse { amount } => try_withdraw_house(deps, info, amount),
+        ExecuteMsg::PlayHouse { player_move, round } => {
+            try_play_house(deps, env, info, player_move, round)
+        }
+        ExecuteMsg::SubmitBeacon {
+            game_id,
+            previous_signature,
+            signature,
+        } => try_submit_beacon(deps, game_id, previous_signature, signature),
+        ExecuteMsg::Rematch {
+            game_id,
+            move_commitment,
+            player,
+        } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_rematch(deps, info, game_id, move_commitment)
+        }
+        ExecuteMsg::AcceptRematch {
+            game_id,
+            move_commitment,
+            player,
+        } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_accept_rematch(deps, env, info, game_id, move_commitment)
+        }
+        ExecuteMsg::CancelRematch { game_id, player } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_cancel_rematch(deps, info, game_id)
+        }
+        ExecuteMsg::ClaimHouseForfeit { game_id } => try_claim_house_forfeit(deps, env, game_id),
+        ExecuteMsg::CreateRound { players, stake } => {
+            try_create_round(deps, env, info, players, stake)
+        }
+        ExecuteMsg::CommitRoundMove {
+            round_id,
+            move_commitment,
+            player,
+        } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_commit_round_move(deps, env, info, round_id, move_commitment)
+        }
+        ExecuteMsg::RevealRoundMove {
+            round_id,
+            player_move,
+            salt,
+            player,
+        } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_reveal_round_move(deps, env, info, round_id, player_move, salt)
+        }
+        ExecuteMsg::ClaimRoundTimeout { round_id } => try_claim_round_timeout(deps, env, round_id),
+        ExecuteMsg::UpdateHillConfig {
+            challenge_fee,
+            defenses_to_cash_out,
+            cash_out_after,
+        } => try_update_hill_config(
             deps,
             info,
-            api.addr_validate(&address.as_str())?,
-        )?),
-        ExecuteMsg::RemoveFromBlacklist { address } => Ok(HOOKS.execute_remove_hook(
-            &ADMIN,
+            challenge_fee,
+            defenses_to_cash_out,
+            cash_out_after,
+        ),
+        ExecuteMsg::ClaimHill {
+            move_commitment,
+            player,
+        } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_claim_hill(deps, env, info, move_commitment)
+        }
+        ExecuteMsg::CommitHillMove {
+            move_commitment,
+            player,
+        } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_commit_hill_move(deps, env, info, move_commitment)
+        }
+        ExecuteMsg::ChallengeHill {
+            challenger_move,
+            player,
+        } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_challenge_hill(deps, env, info, challenger_move)
+        }
+        ExecuteMsg::DefendHill {
+            player_move,
+            salt,
+            next_commitment,
+            player,
+        } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_defend_hill(deps, info, player_move, salt, next_commitment)
+        }
+        ExecuteMsg::ClaimHillTimeout {} => try_claim_hill_timeout(deps, env),
+        ExecuteMsg::CashOutHill { player } => {
+            let info = operator_info(deps.as_ref(), &env, info, player)?;
+            try_cash_out_hill(deps, env, info)
+        }
+        ExecuteMsg::AddSubscriber { address } => {
+            let event = admin_event("add_subscriber", &info.sender, Some(address.to_string()));
+            Ok(SUBSCRIBERS
+                .execute_add_hook(&ADMIN, deps, info, api.addr_validate(address.as_str())?)?
+                .add_event(event))
+        }
+        ExecuteMsg::RemoveSubscriber { address } => {
+            let event = admin_event("remove_subscriber", &info.sender, Some(address.to_string()));
+            Ok(SUBSCRIBERS
+                .execute_remove_hook(&ADMIN, deps, info, api.addr_validate(address.as_str())?)?
+                .add_event(event))
+        }
+        ExecuteMsg::UpdateSubscriberMode { reply_on_error } => {
+            try_update_subscriber_mode(deps, info, reply_on_error)
+        }
+        ExecuteMsg::UpdateTrophyContract { contract } => {
+            try_update_trophy_contract(deps, info, contract)
+        }
+        ExecuteMsg::UpdateRewardConfig {
+            token,
+            daily_cap,
+            schedule,
+        } => try_update_reward_config(deps, info, token, daily_cap, schedule),
+        ExecuteMsg::UpdateStreakConfig {
+            ties_break_streak,
+            milestones,
+        } => try_update_streak_config(deps, info, ties_break_streak, milestones),
+        ExecuteMsg::FundTreasury {} => try_fund_treasury(deps, info),
+        ExecuteMsg::WithdrawTreasury { amount } => try_withdraw_treasury(deps, info, amount),
+        ExecuteMsg::UpdateRankedConfig { config } => try_update_ranked_config(deps, info, config),
+        ExecuteMsg::SetProfile {
+            nickname,
+            avatar_uri,
+            bio,
+        } => try_set_profile(deps, info, nickname, avatar_uri, bio),
+        ExecuteMsg::ClearNickname { player } => try_clear_nickname(deps, info, player),
+        ExecuteMsg::CreateGuild { name } => try_create_guild(deps, info, name),
+        ExecuteMsg::InviteToGuild { player } => try_invite_to_guild(deps, info, player),
+        ExecuteMsg::AcceptGuildInvite { guild_id } => try_accept_guild_invite(deps, info, guild_id),
+        ExecuteMsg::LeaveGuild {} => try_leave_guild(deps, info),
+        ExecuteMsg::DeclareGuildWar {
+            opponent_guild,
+            duration,
+        } => try_declare_guild_war(deps, info, opponent_guild, duration),
+        ExecuteMsg::AcceptGuildWar { war_id } => try_accept_guild_war(deps, env, info, war_id),
+        ExecuteMsg::FinishGuildWar { war_id } => try_finish_guild_war(deps, env, war_id),
+        ExecuteMsg::StartSeason {} => try_start_season(deps, env, info),
+        ExecuteMsg::EndSeason { prizes } => try_end_season(deps, env, info, prizes),
+        ExecuteMsg::GrantOperator {
+            operator,
+            expires,
+            max_wager,
+        } => try_grant_operator(deps, env, info, operator, expires, max_wager),
+        ExecuteMsg::RevokeOperator { operator } => try_revoke_operator(deps, info, operator),
+        ExecuteMsg::RegisterBot {} => try_register_bot(deps, info),
+        ExecuteMsg::UnregisterBot {} => try_unregister_bot(deps, info),
+        ExecuteMsg::PlayBotGame { bot, host_move } => {
+            try_play_bot_game(deps, env, info, bot, host_move)
+        }
+        ExecuteMsg::OpenChannel {
+            counterparty,
+            pubkey,
+        } => try_open_channel(deps, info, counterparty, pubkey),
+        ExecuteMsg::JoinChannel { channel_id, pubkey } => {
+            try_join_channel(deps, info, channel_id, pubkey)
+        }
+        ExecuteMsg::CancelChannel { channel_id } => try_cancel_channel(deps, info, channel_id),
+        ExecuteMsg::SettleChannel { channel_id, state } => {
+            try_settle_channel(deps, env, info, channel_id, state)
+        }
+        ExecuteMsg::DisputeChannelRound {
+            channel_id,
+            move_a,
+            move_b,
+        } => try_dispute_channel_round(deps, env, info, channel_id, move_a, move_b),
+        ExecuteMsg::RevealChannelMove {
+            channel_id,
+            player_move,
+            salt,
+        } => try_reveal_channel_move(deps, env, info, channel_id, player_move, salt),
+        ExecuteMsg::FinalizeChannel { channel_id } => try_finalize_channel(deps, env, channel_id),
+        ExecuteMsg::IbcChallenge {
+            channel_id,
+            opponent,
+            move_commitment,
+            host_payout,
+            opponent_stake,
+        } => try_ibc_challenge(
             deps,
+            env,
             info,
-            api.addr_validate(&address.as_str())?,
-        )?),
-        ExecuteMsg::OpponentResponse {
-            host,
+            channel_id,
             opponent,
+            move_commitment,
+            host_payout,
+            opponent_stake,
+        ),
+        ExecuteMsg::IbcCancelChallenge {
+            channel_id,
+            game_id,
+        } => try_ibc_cancel_challenge(deps, env, info, channel_id, game_id),
+        ExecuteMsg::IbcRespond {
+            channel_id,
+            game_id,
             opp_move,
-        } => try_opponent_response(deps, info, host, opponent, opp_move),
+            opponent_payout,
+        } => try_ibc_respond(
+            deps,
+            env,
+            info,
+            channel_id,
+            game_id,
+            opp_move,
+            opponent_payout,
+        ),
+        ExecuteMsg::IbcRevealMove {
+            channel_id,
+            game_id,
+            host_move,
+            salt,
+        } => try_ibc_reveal_move(deps, env, info, channel_id, game_id, host_move, salt),
+        ExecuteMsg::IbcClaimTimeout {
+            channel_id,
+            game_id,
+        } => try_ibc_claim_timeout(deps, env, info, channel_id, game_id),
+        ExecuteMsg::RelayedMove {
+            signed_payload,
+            pubkey,
+            signature,
+        } => try_relayed_move(deps, env, info, signed_payload, pubkey, signature),
     }
 }
 
-pub fn try_start_game(
+pub fn try_relayed_move(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    opponent: Addr,
-    host_move: GameMove,
+    signed_payload: Binary,
+    pubkey: Binary,
+    signature: Binary,
 ) -> Result<Response, ContractError> {
-    let blacklist = HOOKS.query_hooks(deps.as_ref())?.hooks;
+    //the relayer only pays for gas, funds sent along would be stuck
+    if !info.funds.is_empty() {
+        return Err(StdError::generic_err("relayed moves cannot carry funds").into());
+    }
 
-    for address in blacklist {
-        if address == info.sender {
-            return Err(ContractError::OnTheBlacklist {});
-        }
+    let payload: RelayedPayload = verify_signed(deps.api, &signed_payload, &pubkey, &signature)?;
+
+    if payload.chain_id != env.block.chain_id {
+        return Err(ContractError::WrongChainId {});
     }
 
-    let _valid_addr = deps.api.addr_validate(opponent.as_str())?;
+    //a deployment on the same chain must not accept moves signed for another one
+    if payload.contract != env.contract.address {
+        return Err(ContractError::WrongContract {});
+    }
 
-    let game_found = GAME.may_load(deps.storage, (&info.sender, &opponent))?;
+    let player = deps.api.addr_validate(payload.player.as_str())?;
+    check_pubkey(deps.api, &pubkey, &player)?;
 
-    match game_found {
-        Some(_) => return Err(ContractError::OneGameAtATime {}),
-        None => {
-            let g = Game {
-                host: info.sender.clone(),
-                opponent: opponent.clone(),
-                host_move: host_move,
-                opp_move: None,
-                result: None,
-            };
+    let expected = RELAY_NONCES
+        .may_load(deps.storage, &player)?
+        .unwrap_or_default();
+    if payload.nonce != expected {
+        return Err(ContractError::InvalidNonce { expected });
+    }
+    RELAY_NONCES.save(deps.storage, &player, &(expected + 1))?;
 
-            GAME.save(deps.storage, (&info.sender, &opponent), &g)?;
-        }
+    //the move is played exactly as if the player had sent it without funds
+    let relayed = MessageInfo {
+        sender: player,
+        funds: vec![],
     };
+    let res = match payload.action {
+        RelayedAction::StartGame {
+            opponent,
+            host_move,
+        } => try_start_game(deps, env, relayed, None, opponent, host_move, None),
+        RelayedAction::OpponentResponse { host, opp_move } => {
+            let opponent = relayed.sender.clone();
+            try_opponent_response(deps, env, relayed, host, opponent, opp_move)
+        }
+        RelayedAction::RevealMove {
+            game_id,
+            player_move,
+            salt,
+        } => try_reveal_move(deps, env, relayed, game_id, player_move, salt),
+        RelayedAction::CommitRoundMove {
+            round_id,
+            move_commitment,
+        } => try_commit_round_move(deps, env, relayed, round_id, move_commitment),
+        RelayedAction::RevealRoundMove {
+            round_id,
+            player_move,
+            salt,
+        } => try_reveal_round_move(deps, env, relayed, round_id, player_move, salt),
+    }?;
 
-    Ok(Response::new()
-        .add_attribute("method", "try_start_game")
-        .add_attribute("host", info.sender)
-        .add_attribute("opponent", opponent))
+    Ok(res
+        .add_attribute("relayer", info.sender)
+        .add_attribute("nonce", payload.nonce.to_string()))
 }
 
-pub fn try_opponent_response(
+// only the canonical serialization is accepted, so each payload has exactly one signed form
+fn verify_signed<T: Serialize + DeserializeOwned>(
+    api: &dyn Api,
+    payload: &Binary,
+    pubkey: &[u8],
+    signature: &[u8],
+) -> Result<T, ContractError> {
+    let parsed: T = from_slice(payload)?;
+    if to_vec(&parsed)? != payload.as_slice() {
+        return Err(StdError::generic_err("signed payload is not in canonical form").into());
+    }
+
+    let verified = api
+        .secp256k1_verify(&payload_hash(payload), signature, pubkey)
+        .map_err(|_| ContractError::InvalidSignature {})?;
+    if !verified {
+        return Err(ContractError::InvalidSignature {});
+    }
+
+    Ok(parsed)
+}
+
+fn check_pubkey(api: &dyn Api, pubkey: &[u8], player: &Addr) -> Result<(), ContractError> {
+    let derived = pubkey_to_canonical(pubkey).ok_or(ContractError::PubkeyMismatch {})?;
+    if api.addr_humanize(&derived)? != *player {
+        return Err(ContractError::PubkeyMismatch {});
+    }
+
+    Ok(())
+}
+
+pub fn try_open_channel(
     deps: DepsMut,
     info: MessageInfo,
-    host: Addr,
-    opponent: Addr,
-    opp_move: GameMove,
+    counterparty: Addr,
+    pubkey: Binary,
 ) -> Result<Response, ContractError> {
-    //validate host and opp address
-    let api = deps.api;
-
-    let valid_host = api.addr_validate(&host.as_str())?;
-    let valid_opp = api.addr_validate(&opponent.as_str())?;
-
-    let key = (&valid_host, &valid_opp);
+    check_blacklist(deps.as_ref(), &info.sender)?;
 
-    //check opp & info sender are the same
-    if &info.sender != &opponent {
+    let counterparty = deps.api.addr_validate(counterparty.as_str())?;
+    if counterparty == info.sender {
         return Err(ContractError::Unauthorized {});
     }
 
-    //load game
-    let game_found =
-        query_game_by_host_and_opponent(deps.as_ref(), host.clone(), opponent.clone())?;
+    let deposit = match info.funds.as_slice() {
+        [deposit] if !deposit.amount.is_zero() => deposit.clone(),
+        _ => return Err(ContractError::InvalidWager {}),
+    };
 
-    //compare host move and opp move
-    let result = get_game_result(game_found.host_move, opp_move)?;
+    //off-chain states are signed with this key, so it has to be the player's own
+    check_pubkey(deps.api, &pubkey, &info.sender)?;
 
-    //return the game result
-    let result_str = match result {
-        GameResult::HostWins => "Host Wins",
-        GameResult::OpponentWins => "Opponent Wins",
-        GameResult::Tie => "Tie",
+    let id = CHANNEL_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    CHANNEL_SEQ.save(deps.storage, &id)?;
+
+    let channel = Channel {
+        id,
+        player_a: info.sender.clone(),
+        pubkey_a: pubkey,
+        player_b: counterparty.clone(),
+        pubkey_b: None,
+        balance_a: deposit.amount,
+        balance_b: Uint128::zero(),
+        deposit,
+        status: ChannelStatus::Opening,
+        nonce: 0,
+        closes: None,
+        dispute: None,
     };
+    CHANNELS.save(deps.storage, id, &channel)?;
 
-    //create closure for update method
-    let update_game = |g: Option<Game>| -> Result<Game, ContractError> {
-        match g {
-            Some(_) => Ok(Game {
-                host: game_found.host,
-                opponent: game_found.opponent,
-                host_move: game_found.host_move,
-                opp_move: Some(opp_move),
-                result: Some(result),
-            }),
-            None => Err(ContractError::NoGameFound {}),
-        }
+    let event = GameStartedEvent {
+        kind: GameKind::Channel,
+        game_id: Some(id),
+        host: channel.player_a,
+        opponent: Some(channel.player_b),
+        stake: Some(channel.deposit),
+        fee: None,
     };
 
-    //update game to GAME state
-    GAME.update(deps.storage, key, update_game)?;
+    Ok(Response::new()
+        .add_event(event.into_event())
+        .add_attribute("method", "try_open_channel")
+        .add_attribute("channel_id", id.to_string())
+        .add_attribute("player_a", info.sender)
+        .add_attribute("player_b", counterparty))
+}
 
-    //delete the game from state
-    GAME.remove(deps.storage, key);
+pub fn try_join_channel(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: u64,
+    pubkey: Binary,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
 
-    //optional: add a leaderboard
+    let mut channel = load_channel_in_status(deps.as_ref(), channel_id, ChannelStatus::Opening)?;
+    if info.sender != channel.player_b {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    check_wager_funds(&info, &Some(channel.deposit.clone()))?;
+    check_pubkey(deps.api, &pubkey, &info.sender)?;
+
+    channel.pubkey_b = Some(pubkey);
+    channel.balance_b = channel.deposit.amount;
+    channel.status = ChannelStatus::Open;
+    CHANNELS.save(deps.storage, channel_id, &channel)?;
 
     Ok(Response::new()
-        .add_attribute("method", "try_opponent_response")
-        .add_attribute("host", valid_host)
-        .add_attribute("opponent", valid_opp)
-        .add_attribute("result", result_str))
+        .add_attribute("method", "try_join_channel")
+        .add_attribute("channel_id", channel_id.to_string()))
 }
 
-pub fn get_game_result(
-    host_move: GameMove,
-    opp_move: GameMove,
-) -> Result<GameResult, ContractError> {
-    if host_move == opp_move {
-        Ok(GameResult::Tie)
-    } else if host_move == GameMove::Rock && opp_move == GameMove::Scissors
-        || host_move == GameMove::Paper && opp_move == GameMove::Rock
-        || host_move == GameMove::Scissors && opp_move == GameMove::Paper
-    {
-        Ok(GameResult::HostWins)
-    } else {
-        Ok(GameResult::OpponentWins)
+pub fn try_cancel_channel(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: u64,
+) -> Result<Response, ContractError> {
+    let mut channel = load_channel_in_status(deps.as_ref(), channel_id, ChannelStatus::Opening)?;
+    if info.sender != channel.player_a {
+        return Err(ContractError::Unauthorized {});
     }
-}
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetGamesByHost { host } => to_binary(&query_games_by_host(deps, host)?),
-        QueryMsg::GetGamesByOpponent { opponent } => {
-            to_binary(&query_games_by_opponent(deps, opponent)?)
-        }
-        QueryMsg::GetGameByHostAndOpponent { host, opponent } => {
-            to_binary(&query_game_by_host_and_opponent(deps, host, opponent)?)
-        }
-        QueryMsg::GetAdmin {} => to_binary(&ADMIN.get(deps)?),
-    }
+    let payout = close_channel(&mut channel);
+    CHANNELS.save(deps.storage, channel_id, &channel)?;
+
+    let event = GameCancelledEvent {
+        kind: GameKind::Channel,
+        game_id: Some(channel_id),
+        host: channel.player_a,
+        opponent: Some(channel.player_b),
+        stake: Some(channel.deposit),
+    };
+
+    Ok(Response::new()
+        .add_event(event.into_event())
+        .add_messages(payout)
+        .add_attribute("method", "try_cancel_channel")
+        .add_attribute("channel_id", channel_id.to_string()))
 }
 
-fn query_games_by_host(deps: Deps, host: Addr) -> StdResult<GamesListResponse> {
-    let valid_addr = deps.api.addr_validate(host.as_str())?;
+pub fn try_settle_channel(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: u64,
+    state: SignedChannelState,
+) -> Result<Response, ContractError> {
+    let mut channel = load_contested_channel(deps.as_ref(), &env, &info, channel_id)?;
 
-    let all_games: StdResult<Vec<_>> = GAME
-        .prefix(&valid_addr)
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect();
+    let pubkey_b = channel.pubkey_b.clone().unwrap_or_default();
+    let payload: ChannelState = verify_signed(
+        deps.api,
+        &state.payload,
+        &channel.pubkey_a,
+        &state.signature_a,
+    )?;
+    verify_signed::<ChannelState>(deps.api, &state.payload, &pubkey_b, &state.signature_b)?;
 
-    let mut found_games: Vec<Game> = vec![];
+    check_channel_payload(
+        &env,
+        channel_id,
+        &payload.chain_id,
+        &payload.contract,
+        payload.channel_id,
+    )?;
 
-    for all_games in &all_games? {
-        found_games.push(all_games.1.clone());
+    if payload.nonce <= channel.nonce {
+        return Err(ContractError::StaleChannelState {});
     }
 
-    Ok(GamesListResponse { games: found_games })
+    if payload.balance_a + payload.balance_b != channel.deposit.amount + channel.deposit.amount {
+        return Err(StdError::generic_err("channel balances must add up to both deposits").into());
+    }
+
+    //the tally covers any disputed round, which is dropped
+    channel.nonce = payload.nonce;
+    channel.balance_a = payload.balance_a;
+    channel.balance_b = payload.balance_b;
+    channel.dispute = None;
+
+    //a final state is agreed by both players, so there is nothing left to challenge
+    let res = if payload.is_final {
+        Response::new()
+            .add_messages(close_channel(&mut channel))
+            .add_event(channel_event(&channel))
+    } else {
+        start_channel_challenge(&mut channel, &env);
+        Response::new()
+    };
+    CHANNELS.save(deps.storage, channel_id, &channel)?;
+
+    Ok(res
+        .add_attribute("method", "try_settle_channel")
+        .add_attribute("channel_id", channel_id.to_string())
+        .add_attribute("nonce", channel.nonce.to_string()))
 }
 
-fn query_games_by_opponent(deps: Deps, opponent: Addr) -> StdResult<GamesListResponse> {
-    let valid_addr = deps.api.addr_validate(opponent.as_str())?;
+pub fn try_dispute_channel_round(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: u64,
+    move_a: SignedChannelMove,
+    move_b: SignedChannelMove,
+) -> Result<Response, ContractError> {
+    let mut channel = load_contested_channel(deps.as_ref(), &env, &info, channel_id)?;
 
-    let all_games: StdResult<Vec<_>> = GAME
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect();
+    let pubkey_b = channel.pubkey_b.clone().unwrap_or_default();
+    let a: ChannelMove = verify_signed(
+        deps.api,
+        &move_a.payload,
+        &channel.pubkey_a,
+        &move_a.signature,
+    )?;
+    let b: ChannelMove = verify_signed(deps.api, &move_b.payload, &pubkey_b, &move_b.signature)?;
 
-    let mut found_games: Vec<Game> = vec![];
+    check_channel_payload(&env, channel_id, &a.chain_id, &a.contract, a.channel_id)?;
+    check_channel_payload(&env, channel_id, &b.chain_id, &b.contract, b.channel_id)?;
 
-    for all_games in &all_games? {
-        if all_games.1.opponent == valid_addr {
-            found_games.push(all_games.1.clone());
-        }
+    if channel.dispute.is_some() {
+        return Err(ContractError::ChannelRoundDisputed {});
     }
 
-    Ok(GamesListResponse { games: found_games })
-}
+    //both moves must be for the round right after the settled tally
+    if a.nonce != channel.nonce + 1 || b.nonce != channel.nonce + 1 {
+        return Err(ContractError::StaleChannelState {});
+    }
 
-fn query_game_by_host_and_opponent(deps: Deps, host: Addr, opponent: Addr) -> StdResult<Game> {
-    let valid_host = deps.api.addr_validate(host.as_str())?;
-    let valid_opp = deps.api.addr_validate(opponent.as_str())?;
+    if a.stake != b.stake {
+        return Err(StdError::generic_err("both moves must be for the same stake").into());
+    }
 
-    let game = GAME.may_load(deps.storage, (&valid_host, &valid_opp))?;
+    if a.stake > channel.balance_a || a.stake > channel.balance_b {
+        return Err(StdError::generic_err("stake exceeds a player's balance").into());
+    }
 
-    match game {
-        Some(g) => Ok(Game {
-            host: valid_host,
-            opponent: valid_opp,
-            host_move: g.host_move,
-            opp_move: g.opp_move,
-            result: g.result,
-        }),
-        None => Err(StdError::generic_err("No game found")),
+    if a.move_commitment.len() != 32 || b.move_commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::state::GameMove;
+    //the moves were committed blindly, they are only decided once both are revealed
+    channel.dispute = Some(ChannelDispute {
+        stake: a.stake,
+        commitment_a: a.move_commitment,
+        commitment_b: b.move_commitment,
+        move_a: None,
+        move_b: None,
+        reveal_expires: CHANNEL_REVEAL_TIMEOUT.after(&env.block),
+    });
+    start_channel_challenge(&mut channel, &env);
+    CHANNELS.save(deps.storage, channel_id, &channel)?;
 
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary};
+    Ok(Response::new()
+        .add_attribute("method", "try_dispute_channel_round")
+        .add_attribute("channel_id", channel_id.to_string())
+        .add_attribute("nonce", (channel.nonce + 1).to_string()))
+}
 
-    #[test]
-    fn proper_initialization() {
-        let mut deps = mock_dependencies();
+pub fn try_reveal_channel_move(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: u64,
+    player_move: GameMove,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let mut channel = CHANNELS
+        .may_load(deps.storage, channel_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+    let mut dispute = channel
+        .dispute
+        .clone()
+        .ok_or(ContractError::NoChannelDispute {})?;
 
-        let msg = InstantiateMsg {
-            admin: Addr::unchecked("creator"),
-        };
-        let info = mock_info("creator", &coins(1000, "earth"));
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    if dispute.reveal_expires.is_expired(&env.block) {
+        return Err(ContractError::RevealExpired {});
+    }
 
-        //confirm instantiation response
-        assert_eq!("owner", res.attributes[1].key);
-        assert_eq!("creator", res.attributes[1].value);
+    let (commitment, revealed) = if info.sender == channel.player_a {
+        (&dispute.commitment_a, &mut dispute.move_a)
+    } else if info.sender == channel.player_b {
+        (&dispute.commitment_b, &mut dispute.move_b)
+    } else {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    if revealed.is_some() {
+        return Err(ContractError::AlreadyRevealed {});
     }
 
-    #[test]
-    fn start_game() {
-        let mut deps = mock_dependencies();
+    verify_move(player_move, &salt, commitment)?;
+    *revealed = Some(player_move);
 
-        let msg = InstantiateMsg {
-            admin: Addr::unchecked("creator"),
-        };
-        let info = mock_info("creator", &coins(1000, "earth"));
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let res = Response::new()
+        .add_attribute("method", "try_reveal_channel_move")
+        .add_attribute("channel_id", channel_id.to_string())
+        .add_attribute("player", info.sender);
 
-        //confirm instantiation response
-        assert_eq!("owner", res.attributes[1].key);
-        assert_eq!("creator", res.attributes[1].value);
+    //player a plays the host side of a regular game
+    let res = match (dispute.move_a, dispute.move_b) {
+        (Some(move_a), Some(move_b)) => {
+            let result = get_game_result(move_a, move_b)?;
+            settle_channel_round(&mut channel, dispute.stake, result);
+            res.add_attribute("result", result_str(&result))
+        }
+        _ => {
+            channel.dispute = Some(dispute);
+            res
+        }
+    };
+    CHANNELS.save(deps.storage, channel_id, &channel)?;
 
-        // execute start game
-        let auth_info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
-            host_move: GameMove::Rock,
-        };
+    Ok(res)
+}
 
-        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+pub fn try_finalize_channel(
+    deps: DepsMut,
+    env: Env,
+    channel_id: u64,
+) -> Result<Response, ContractError> {
+    let mut channel = load_channel_in_status(deps.as_ref(), channel_id, ChannelStatus::Closing)?;
 
-        assert_eq!("method", res.attributes[0].key);
-        assert_eq!("try_start_game", res.attributes[0].value);
-        assert_eq!("host", res.attributes[1].key);
-        assert_eq!("creator", res.attributes[1].value);
+    if !channel
+        .closes
+        .is_some_and(|closes| closes.is_expired(&env.block))
+    {
+        return Err(ContractError::ChallengePeriodNotOver {});
+    }
+
+    //a player who did not reveal a disputed move forfeits the round
+    if let Some(dispute) = channel.dispute.clone() {
+        if !dispute.reveal_expires.is_expired(&env.block) {
+            return Err(ContractError::ChallengePeriodNotOver {});
+        }
+
+        match (dispute.move_a, dispute.move_b) {
+            (Some(_), None) => {
+                settle_channel_round(&mut channel, dispute.stake, GameResult::HostWins)
+            }
+            (None, Some(_)) => {
+                settle_channel_round(&mut channel, dispute.stake, GameResult::OpponentWins)
+            }
+            _ => channel.dispute = None,
+        }
+    }
+
+    let payout = close_channel(&mut channel);
+    CHANNELS.save(deps.storage, channel_id, &channel)?;
+
+    Ok(Response::new()
+        .add_event(channel_event(&channel))
+        .add_messages(payout)
+        .add_attribute("method", "try_finalize_channel")
+        .add_attribute("channel_id", channel_id.to_string())
+        .add_attribute("balance_a", channel.balance_a)
+        .add_attribute("balance_b", channel.balance_b))
+}
+
+fn load_channel_in_status(
+    deps: Deps,
+    channel_id: u64,
+    status: ChannelStatus,
+) -> Result<Channel, ContractError> {
+    let channel = CHANNELS
+        .may_load(deps.storage, channel_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if channel.status != status {
+        return Err(ContractError::WrongChannelStatus {
+            status: format!("{:?}", status),
+        });
+    }
+
+    Ok(channel)
+}
+
+// an open channel, or a closing one whose challenge period is still running
+fn load_contested_channel(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    channel_id: u64,
+) -> Result<Channel, ContractError> {
+    let channel = CHANNELS
+        .may_load(deps.storage, channel_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if info.sender != channel.player_a && info.sender != channel.player_b {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match channel.status {
+        ChannelStatus::Open => {}
+        ChannelStatus::Closing => {
+            if channel
+                .closes
+                .is_some_and(|closes| closes.is_expired(&env.block))
+            {
+                return Err(ContractError::ChallengePeriodOver {});
+            }
+        }
+        _ => {
+            return Err(ContractError::WrongChannelStatus {
+                status: "Open or Closing".to_string(),
+            })
+        }
+    }
+
+    Ok(channel)
+}
+
+// signed payloads name the chain, contract and channel so they cannot be replayed elsewhere
+fn check_channel_payload(
+    env: &Env,
+    channel_id: u64,
+    chain_id: &str,
+    contract: &Addr,
+    payload_channel_id: u64,
+) -> Result<(), ContractError> {
+    if chain_id != env.block.chain_id {
+        return Err(ContractError::WrongChainId {});
+    }
+
+    if *contract != env.contract.address || payload_channel_id != channel_id {
+        return Err(StdError::generic_err("signed payload is for another channel").into());
+    }
+
+    Ok(())
+}
+
+// moves the stake of the disputed round to its winner, the stake was checked
+// against both balances when the dispute was opened
+fn settle_channel_round(channel: &mut Channel, stake: Uint128, result: GameResult) {
+    match result {
+        GameResult::HostWins => {
+            channel.balance_b -= stake;
+            channel.balance_a += stake;
+        }
+        GameResult::OpponentWins => {
+            channel.balance_a -= stake;
+            channel.balance_b += stake;
+        }
+        GameResult::Tie => {}
+    }
+
+    channel.nonce += 1;
+    channel.dispute = None;
+}
+
+// the challenge period starts with the first submitted state and is not extended
+fn start_channel_challenge(channel: &mut Channel, env: &Env) {
+    if channel.status == ChannelStatus::Open {
+        channel.status = ChannelStatus::Closing;
+        channel.closes = Some(CHANNEL_CHALLENGE_PERIOD.after(&env.block));
+    }
+}
+
+fn close_channel(channel: &mut Channel) -> Vec<BankMsg> {
+    channel.status = ChannelStatus::Closed;
+
+    [
+        (&channel.player_a, channel.balance_a),
+        (&channel.player_b, channel.balance_b),
+    ]
+    .iter()
+    .filter(|(_, amount)| !amount.is_zero())
+    .map(|(to, amount)| BankMsg::Send {
+        to_address: to.to_string(),
+        amount: vec![Coin {
+            denom: channel.deposit.denom.clone(),
+            amount: *amount,
+        }],
+    })
+    .collect()
+}
+
+// resolved event for a closed channel, the player who ends up with more won
+fn channel_event(channel: &Channel) -> Event {
+    let result = match channel.balance_a.cmp(&channel.balance_b) {
+        Ordering::Greater => GameResult::HostWins,
+        Ordering::Less => GameResult::OpponentWins,
+        Ordering::Equal => GameResult::Tie,
+    };
+
+    GameResolvedEvent {
+        kind: GameKind::Channel,
+        game_id: Some(channel.id),
+        record_id: None,
+        host: channel.player_a.clone(),
+        opponent: Some(channel.player_b.clone()),
+        host_move: None,
+        opp_move: None,
+        result,
+        stake: Some(channel.deposit.clone()),
+        fee: None,
+    }
+    .into_event()
+}
+
+pub fn try_update_subscriber_mode(
+    deps: DepsMut,
+    info: MessageInfo,
+    reply_on_error: bool,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    SUBSCRIBER_REPLY_ON_ERROR.save(deps.storage, &reply_on_error)?;
+
+    Ok(Response::new()
+        .add_event(admin_event(
+            "update_subscriber_mode",
+            &info.sender,
+            Some(reply_on_error.to_string()),
+        ))
+        .add_attribute("method", "try_update_subscriber_mode")
+        .add_attribute("reply_on_error", reply_on_error.to_string()))
+}
+
+pub fn try_update_trophy_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: Option<Addr>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let contract = match contract {
+        Some(contract) => Some(deps.api.addr_validate(contract.as_str())?),
+        None => None,
+    };
+
+    STATE.update(deps.storage, |mut state| -> StdResult<_> {
+        state.trophy_contract = contract.clone();
+        Ok(state)
+    })?;
+
+    Ok(Response::new()
+        .add_event(admin_event(
+            "update_trophy_contract",
+            &info.sender,
+            contract.as_ref().map(Addr::to_string),
+        ))
+        .add_attribute("method", "try_update_trophy_contract"))
+}
+
+//mints a trophy to the winner when a trophy contract is configured
+fn trophy_mint(
+    storage: &dyn Storage,
+    token_id: String,
+    owner: &Addr,
+    metadata: TrophyMetadata,
+) -> StdResult<Option<SubMsg>> {
+    let contract = match STATE.load(storage)?.trophy_contract {
+        Some(contract) => contract,
+        None => return Ok(None),
+    };
+
+    let msg = TrophyMintMsg {
+        token_id,
+        owner: owner.to_string(),
+        token_uri: None,
+        extension: metadata,
+    }
+    .into_cosmos_msg(contract)?;
+
+    Ok(Some(SubMsg::reply_on_error(msg, TROPHY_REPLY_ID)))
+}
+
+pub fn try_update_reward_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    token: Addr,
+    daily_cap: Uint128,
+    schedule: Vec<EmissionRate>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if schedule.windows(2).any(|w| w[0].start >= w[1].start) {
+        return Err(ContractError::InvalidEmissionSchedule {});
+    }
+
+    let config = RewardConfig {
+        token: deps.api.addr_validate(token.as_str())?,
+        daily_cap,
+        schedule,
+    };
+    REWARD_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_event(admin_event(
+            "update_reward_config",
+            &info.sender,
+            Some(config.token.to_string()),
+        ))
+        .add_attribute("method", "try_update_reward_config"))
+}
+
+//daily reward caps reset at midnight utc
+fn reward_day(env: &Env) -> u64 {
+    env.block.time.seconds() / (24 * 60 * 60)
+}
+
+fn minted_today(storage: &dyn Storage, env: &Env, player: &Addr) -> StdResult<Uint128> {
+    let today = reward_day(env);
+    Ok(DAILY_REWARDS
+        .may_load(storage, player)?
+        .filter(|rewards| rewards.day == today)
+        .map(|rewards| rewards.minted)
+        .unwrap_or_default())
+}
+
+//mints the rate in effect to both players of a game, capped per player and day
+fn reward_mints(
+    storage: &mut dyn Storage,
+    env: &Env,
+    players: [(&Addr, bool); 2],
+) -> StdResult<Vec<SubMsg>> {
+    let config = match REWARD_CONFIG.may_load(storage)? {
+        Some(config) => config,
+        None => return Ok(vec![]),
+    };
+    let rate = match config
+        .schedule
+        .iter()
+        .rev()
+        .find(|rate| rate.start <= env.block.time)
+    {
+        Some(rate) => rate,
+        None => return Ok(vec![]),
+    };
+
+    let today = reward_day(env);
+    let mut mints = vec![];
+    for (player, won) in players {
+        let earned = if won {
+            rate.per_game + rate.per_win
+        } else {
+            rate.per_game
+        };
+
+        let minted = minted_today(storage, env, player)?;
+        let amount = earned.min(config.daily_cap.saturating_sub(minted));
+        if amount.is_zero() {
+            continue;
+        }
+
+        let rewards = DailyRewards {
+            day: today,
+            minted: minted + amount,
+        };
+        DAILY_REWARDS.save(storage, player, &rewards)?;
+
+        let msg = Cw20ExecuteMsg::Mint {
+            recipient: player.to_string(),
+            amount,
+        }
+        .into_cosmos_msg(&config.token)?;
+        mints.push(SubMsg::reply_on_error(msg, REWARD_REPLY_ID));
+    }
+
+    Ok(mints)
+}
+
+pub fn try_update_streak_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    ties_break_streak: bool,
+    milestones: Vec<StreakMilestone>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let config = StreakConfig {
+        ties_break_streak,
+        milestones,
+    };
+    STREAK_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_event(admin_event("update_streak_config", &info.sender, None))
+        .add_attribute("method", "try_update_streak_config"))
+}
+
+pub fn try_fund_treasury(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    for fund in &info.funds {
+        TREASURY.update(deps.storage, &fund.denom, |treasury| -> StdResult<_> {
+            Ok(treasury.unwrap_or_default() + fund.amount)
+        })?;
+    }
+
+    Ok(Response::new()
+        .add_event(admin_event("fund_treasury", &info.sender, None))
+        .add_attribute("method", "try_fund_treasury"))
+}
+
+pub fn try_withdraw_treasury(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Coin,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    TREASURY.update(deps.storage, &amount.denom, |treasury| {
+        treasury
+            .unwrap_or_default()
+            .checked_sub(amount.amount)
+            .map_err(|_| ContractError::InsufficientTreasury {})
+    })?;
+
+    Ok(Response::new()
+        .add_event(admin_event(
+            "withdraw_treasury",
+            &info.sender,
+            Some(amount.to_string()),
+        ))
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![amount],
+        })
+        .add_attribute("method", "try_withdraw_treasury"))
+}
+
+//pays the bonus of a milestone the streak just reached, as far as the treasury allows
+fn streak_milestone(
+    storage: &mut dyn Storage,
+    player: &Addr,
+    streak: u64,
+    metadata: &TrophyMetadata,
+) -> StdResult<Vec<SubMsg>> {
+    let milestone = match STREAK_CONFIG
+        .may_load(storage)?
+        .and_then(|config| config.milestones.into_iter().find(|m| m.streak == streak))
+    {
+        Some(milestone) => milestone,
+        None => return Ok(vec![]),
+    };
+
+    let mut msgs = vec![];
+    let bonus = milestone.bonus;
+    let treasury = TREASURY
+        .may_load(storage, &bonus.denom)?
+        .unwrap_or_default();
+    if !bonus.amount.is_zero() && treasury >= bonus.amount {
+        TREASURY.save(storage, &bonus.denom, &(treasury - bonus.amount))?;
+        msgs.push(SubMsg::new(BankMsg::Send {
+            to_address: player.to_string(),
+            amount: vec![bonus],
+        }));
+    }
+
+    let metadata = TrophyMetadata {
+        kind: TrophyKind::StreakMilestone { streak },
+        ..metadata.clone()
+    };
+    msgs.extend(trophy_mint(
+        storage,
+        format!("streak-{}", metadata.game_id),
+        player,
+        metadata,
+    )?);
+
+    Ok(msgs)
+}
+
+pub fn try_update_ranked_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<RankedConfig>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    match config {
+        Some(config) => {
+            if config.max_pair_games == 0 || config.window == 0 || config.max_alternations == 0 {
+                return Err(ContractError::InvalidRankedConfig {});
+            }
+            RANKED_CONFIG.save(deps.storage, &config)?
+        }
+        None => RANKED_CONFIG.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_event(admin_event("update_ranked_config", &info.sender, None))
+        .add_attribute("method", "try_update_ranked_config"))
+}
+
+//decides whether a game counts toward the standings and records it for the pair
+fn check_ranked(
+    storage: &mut dyn Storage,
+    env: &Env,
+    host: &Addr,
+    opponent: &Addr,
+    result: GameResult,
+    wager: &Option<Coin>,
+) -> StdResult<bool> {
+    if host == opponent {
+        return Ok(false);
+    }
+    let config = match RANKED_CONFIG.may_load(storage)? {
+        Some(config) => config,
+        None => return Ok(true),
+    };
+
+    let (first, second) = ordered_pair(host, opponent);
+    let mut activity = match PAIR_ACTIVITY.may_load(storage, (first, second))? {
+        Some(activity) if env.block.time < activity.window_start.plus_seconds(config.window) => {
+            activity
+        }
+        Some(activity) => PairActivity {
+            window_start: env.block.time,
+            ranked_games: 0,
+            alternations: 0,
+            ..activity
+        },
+        None => PairActivity {
+            window_start: env.block.time,
+            ranked_games: 0,
+            last_winner: None,
+            alternations: 0,
+        },
+    };
+
+    //a pair taking turns winning is likely throwing games, ties in between
+    //do not hide the flips
+    let winner = match result {
+        GameResult::HostWins => Some(host),
+        GameResult::OpponentWins => Some(opponent),
+        GameResult::Tie => None,
+    };
+    if let Some(winner) = winner {
+        if activity
+            .last_winner
+            .as_ref()
+            .is_some_and(|last| last != winner)
+        {
+            activity.alternations += 1;
+        }
+        activity.last_winner = Some(winner.clone());
+    }
+
+    let staked = match &config.min_stake {
+        Some(min) => wager
+            .as_ref()
+            .is_some_and(|wager| wager.denom == min.denom && wager.amount >= min.amount),
+        None => true,
+    };
+    let ranked = staked
+        && activity.ranked_games < config.max_pair_games
+        && activity.alternations < config.max_alternations;
+    if ranked {
+        activity.ranked_games += 1;
+    }
+
+    PAIR_ACTIVITY.save(storage, (first, second), &activity)?;
+    Ok(ranked)
+}
+
+pub fn try_set_profile(
+    deps: DepsMut,
+    info: MessageInfo,
+    nickname: Option<String>,
+    avatar_uri: Option<String>,
+    bio: Option<String>,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    if let Some(nickname) = &nickname {
+        if !NICKNAME_LENGTH.contains(&nickname.len())
+            || !nickname
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(ContractError::InvalidNickname {});
+        }
+    }
+    if avatar_uri.as_ref().map_or(0, String::len) > MAX_AVATAR_URI_LENGTH
+        || bio.as_ref().map_or(0, String::len) > MAX_BIO_LENGTH
+    {
+        return Err(ContractError::ProfileTooLong {});
+    }
+
+    //release the old nickname before claiming the new one, it may only change case
+    let old = PROFILES.may_load(deps.storage, &info.sender)?;
+    if let Some(old_nickname) = old.and_then(|profile| profile.nickname) {
+        NICKNAMES.remove(deps.storage, &old_nickname.to_lowercase());
+    }
+    if let Some(nickname) = &nickname {
+        let key = nickname.to_lowercase();
+        if NICKNAMES.has(deps.storage, &key) {
+            return Err(ContractError::NicknameTaken {});
+        }
+        NICKNAMES.save(deps.storage, &key, &info.sender)?;
+    }
+
+    let profile = Profile {
+        nickname,
+        avatar_uri,
+        bio,
+    };
+    PROFILES.save(deps.storage, &info.sender, &profile)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_set_profile")
+        .add_attribute("player", info.sender)
+        .add_attribute("nickname", profile.nickname.unwrap_or_default()))
+}
+
+pub fn try_clear_nickname(
+    deps: DepsMut,
+    info: MessageInfo,
+    player: Addr,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut profile = PROFILES
+        .may_load(deps.storage, &player)?
+        .unwrap_or_default();
+    let nickname = profile
+        .nickname
+        .take()
+        .ok_or(ContractError::NicknameNotFound {})?;
+
+    NICKNAMES.remove(deps.storage, &nickname.to_lowercase());
+    PROFILES.save(deps.storage, &player, &profile)?;
+
+    Ok(Response::new()
+        .add_event(admin_event(
+            "clear_nickname",
+            &info.sender,
+            Some(player.to_string()),
+        ))
+        .add_attribute("method", "try_clear_nickname")
+        .add_attribute("player", player))
+}
+
+//opponents given as "@nickname" are looked up in the nickname registry
+fn resolve_opponent(deps: Deps, opponent: Addr) -> Result<Addr, ContractError> {
+    match opponent.as_str().strip_prefix('@') {
+        Some(nickname) => NICKNAMES
+            .may_load(deps.storage, &nickname.to_lowercase())?
+            .ok_or(ContractError::NicknameNotFound {}),
+        None => Ok(opponent),
+    }
+}
+
+pub fn try_create_guild(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    if !GUILD_NAME_LENGTH.contains(&name.len())
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '_' || c == '-')
+    {
+        return Err(ContractError::InvalidGuildName {});
+    }
+    if GUILD_MEMBERS.has(deps.storage, &info.sender) {
+        return Err(ContractError::AlreadyInGuild {});
+    }
+
+    let key = name.to_lowercase();
+    if GUILD_NAMES.has(deps.storage, &key) {
+        return Err(ContractError::GuildNameTaken {});
+    }
+
+    let id = GUILD_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    GUILD_SEQ.save(deps.storage, &id)?;
+
+    let guild = Guild {
+        id,
+        name,
+        leader: info.sender.clone(),
+        members: vec![info.sender.clone()],
+        score: SeasonScore::default(),
+    };
+    GUILDS.save(deps.storage, id, &guild)?;
+    GUILD_NAMES.save(deps.storage, &key, &id)?;
+    GUILD_MEMBERS.save(deps.storage, &info.sender, &id)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_create_guild")
+        .add_attribute("guild_id", id.to_string())
+        .add_attribute("leader", info.sender))
+}
+
+//loads the guild the sender leads
+fn load_led_guild(deps: Deps, leader: &Addr) -> Result<Guild, ContractError> {
+    let guild_id = GUILD_MEMBERS
+        .may_load(deps.storage, leader)?
+        .ok_or(ContractError::NotInGuild {})?;
+    let guild = GUILDS.load(deps.storage, guild_id)?;
+
+    if &guild.leader != leader {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(guild)
+}
+
+pub fn try_invite_to_guild(
+    deps: DepsMut,
+    info: MessageInfo,
+    player: Addr,
+) -> Result<Response, ContractError> {
+    let guild = load_led_guild(deps.as_ref(), &info.sender)?;
+    let player = deps.api.addr_validate(player.as_str())?;
+
+    if GUILD_MEMBERS.has(deps.storage, &player) {
+        return Err(ContractError::AlreadyInGuild {});
+    }
+    if guild.members.len() >= MAX_GUILD_MEMBERS {
+        return Err(ContractError::GuildFull {});
+    }
+
+    GUILD_INVITES.save(deps.storage, (guild.id, &player), &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_invite_to_guild")
+        .add_attribute("guild_id", guild.id.to_string())
+        .add_attribute("player", player))
+}
+
+pub fn try_accept_guild_invite(
+    deps: DepsMut,
+    info: MessageInfo,
+    guild_id: u64,
+) -> Result<Response, ContractError> {
+    if !GUILD_INVITES.has(deps.storage, (guild_id, &info.sender)) {
+        return Err(ContractError::NoGuildInvite {});
+    }
+    if GUILD_MEMBERS.has(deps.storage, &info.sender) {
+        return Err(ContractError::AlreadyInGuild {});
+    }
+
+    //the guild may have been disbanded since the invite
+    let mut guild = GUILDS
+        .may_load(deps.storage, guild_id)?
+        .ok_or(ContractError::GuildNotFound {})?;
+    if guild.members.len() >= MAX_GUILD_MEMBERS {
+        return Err(ContractError::GuildFull {});
+    }
+
+    guild.members.push(info.sender.clone());
+    GUILDS.save(deps.storage, guild_id, &guild)?;
+    GUILD_MEMBERS.save(deps.storage, &info.sender, &guild_id)?;
+    GUILD_INVITES.remove(deps.storage, (guild_id, &info.sender));
+
+    Ok(Response::new()
+        .add_attribute("method", "try_accept_guild_invite")
+        .add_attribute("guild_id", guild_id.to_string())
+        .add_attribute("player", info.sender))
+}
+
+pub fn try_leave_guild(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let guild_id = GUILD_MEMBERS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotInGuild {})?;
+    let mut guild = GUILDS.load(deps.storage, guild_id)?;
+
+    guild.members.retain(|member| member != &info.sender);
+    GUILD_MEMBERS.remove(deps.storage, &info.sender);
+
+    //the last member out disbands the guild
+    match guild.members.first() {
+        Some(next) => {
+            guild.leader = next.clone();
+            GUILDS.save(deps.storage, guild_id, &guild)?;
+        }
+        None => {
+            GUILDS.remove(deps.storage, guild_id);
+            GUILD_NAMES.remove(deps.storage, &guild.name.to_lowercase());
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "try_leave_guild")
+        .add_attribute("guild_id", guild_id.to_string())
+        .add_attribute("player", info.sender))
+}
+
+pub fn try_declare_guild_war(
+    deps: DepsMut,
+    info: MessageInfo,
+    opponent_guild: u64,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    let guild = load_led_guild(deps.as_ref(), &info.sender)?;
+
+    if opponent_guild == guild.id || !GUILDS.has(deps.storage, opponent_guild) {
+        return Err(ContractError::GuildNotFound {});
+    }
+    if duration == 0 {
+        return Err(ContractError::InvalidGuildWar {});
+    }
+
+    let id = GUILD_WAR_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    GUILD_WAR_SEQ.save(deps.storage, &id)?;
+
+    let war = GuildWar {
+        id,
+        guild_a: guild.id,
+        guild_b: opponent_guild,
+        duration,
+        ends: None,
+        wins_a: 0,
+        wins_b: 0,
+        status: GuildWarStatus::Declared,
+        winner: None,
+    };
+    GUILD_WARS.save(deps.storage, id, &war)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_declare_guild_war")
+        .add_attribute("war_id", id.to_string())
+        .add_attribute("guild_a", guild.id.to_string())
+        .add_attribute("guild_b", opponent_guild.to_string()))
+}
+
+pub fn try_accept_guild_war(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    war_id: u64,
+) -> Result<Response, ContractError> {
+    let mut war = load_guild_war_in_status(deps.as_ref(), war_id, GuildWarStatus::Declared)?;
+
+    let guild = load_led_guild(deps.as_ref(), &info.sender)?;
+    if guild.id != war.guild_b {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !GUILDS.has(deps.storage, war.guild_a) {
+        return Err(ContractError::GuildNotFound {});
+    }
+
+    //two guilds fight one war at a time
+    let pair = guild_pair(war.guild_a, war.guild_b);
+    if ACTIVE_GUILD_WARS.has(deps.storage, pair) {
+        return Err(ContractError::InvalidGuildWar {});
+    }
+
+    war.status = GuildWarStatus::Active;
+    war.ends = Some(env.block.time.plus_seconds(war.duration));
+    GUILD_WARS.save(deps.storage, war_id, &war)?;
+    ACTIVE_GUILD_WARS.save(deps.storage, pair, &war_id)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_accept_guild_war")
+        .add_attribute("war_id", war_id.to_string()))
+}
+
+pub fn try_finish_guild_war(
+    deps: DepsMut,
+    env: Env,
+    war_id: u64,
+) -> Result<Response, ContractError> {
+    let mut war = load_guild_war_in_status(deps.as_ref(), war_id, GuildWarStatus::Active)?;
+
+    if war.ends.is_some_and(|ends| env.block.time < ends) {
+        return Err(ContractError::GuildWarNotOver {});
+    }
+
+    //an even war has no winner
+    war.winner = match war.wins_a.cmp(&war.wins_b) {
+        std::cmp::Ordering::Greater => Some(war.guild_a),
+        std::cmp::Ordering::Less => Some(war.guild_b),
+        std::cmp::Ordering::Equal => None,
+    };
+    war.status = GuildWarStatus::Finished;
+    GUILD_WARS.save(deps.storage, war_id, &war)?;
+    ACTIVE_GUILD_WARS.remove(deps.storage, guild_pair(war.guild_a, war.guild_b));
+
+    Ok(Response::new()
+        .add_attribute("method", "try_finish_guild_war")
+        .add_attribute("war_id", war_id.to_string())
+        .add_attribute(
+            "winner",
+            war.winner.map(|id| id.to_string()).unwrap_or_default(),
+        ))
+}
+
+fn load_guild_war_in_status(
+    deps: Deps,
+    war_id: u64,
+    status: GuildWarStatus,
+) -> Result<GuildWar, ContractError> {
+    let war = GUILD_WARS
+        .may_load(deps.storage, war_id)?
+        .ok_or(ContractError::GuildNotFound {})?;
+
+    if war.status != status {
+        return Err(ContractError::InvalidGuildWar {});
+    }
+
+    Ok(war)
+}
+
+fn guild_pair(a: u64, b: u64) -> (u64, u64) {
+    (a.min(b), a.max(b))
+}
+
+//games between members of different guilds count for both guilds and their war
+fn update_guild_scores(storage: &mut dyn Storage, env: &Env, record: &GameRecord) -> StdResult<()> {
+    //unranked games count toward neither the standings nor a war
+    if !record.ranked {
+        return Ok(());
+    }
+    let host_guild = GUILD_MEMBERS.may_load(storage, &record.host)?;
+    let opp_guild = GUILD_MEMBERS.may_load(storage, &record.opponent)?;
+    let (host_guild, opp_guild) = match (host_guild, opp_guild) {
+        (Some(host_guild), Some(opp_guild)) if host_guild != opp_guild => (host_guild, opp_guild),
+        _ => return Ok(()),
+    };
+
+    for (guild_id, win) in [
+        (host_guild, GameResult::HostWins),
+        (opp_guild, GameResult::OpponentWins),
+    ] {
+        GUILDS.update(storage, guild_id, |guild| -> StdResult<_> {
+            let mut guild = guild.ok_or_else(|| StdError::not_found("guild"))?;
+            if record.result == GameResult::Tie {
+                guild.score.ties += 1;
+            } else if record.result == win {
+                guild.score.wins += 1;
+            } else {
+                guild.score.losses += 1;
+            }
+            Ok(guild)
+        })?;
+    }
+
+    let winner = match record.result {
+        GameResult::HostWins => host_guild,
+        GameResult::OpponentWins => opp_guild,
+        GameResult::Tie => return Ok(()),
+    };
+    let war_id = match ACTIVE_GUILD_WARS.may_load(storage, guild_pair(host_guild, opp_guild))? {
+        Some(war_id) => war_id,
+        None => return Ok(()),
+    };
+    let mut war = GUILD_WARS.load(storage, war_id)?;
+    if war.ends.is_some_and(|ends| env.block.time >= ends) {
+        return Ok(());
+    }
+    if winner == war.guild_a {
+        war.wins_a += 1;
+    } else {
+        war.wins_b += 1;
+    }
+    GUILD_WARS.save(storage, war_id, &war)
+}
+
+pub fn try_start_season(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if CURRENT_SEASON.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::SeasonInProgress {});
+    }
+
+    let number = SEASON_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    SEASON_SEQ.save(deps.storage, &number)?;
+
+    let season = Season {
+        number,
+        started_at: env.block.time,
+        ended_at: None,
+        standings: vec![],
+        prizes: vec![],
+    };
+    CURRENT_SEASON.save(deps.storage, &season)?;
+
+    Ok(Response::new()
+        .add_event(admin_event(
+            "start_season",
+            &info.sender,
+            Some(number.to_string()),
+        ))
+        .add_attribute("method", "try_start_season")
+        .add_attribute("season", number.to_string()))
+}
+
+//archives the top standings and pays the prizes to the top players
+pub fn try_end_season(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    prizes: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut season = CURRENT_SEASON
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoActiveSeason {})?;
+
+    if prizes.len() > SEASON_ARCHIVE_SIZE {
+        return Err(ContractError::TooManyPrizes {});
+    }
+
+    let mut total = vec![];
+    for prize in &prizes {
+        add_coin(&mut total, prize);
+    }
+    let mut funds = info.funds.clone();
+    total.sort_by(|a, b| a.denom.cmp(&b.denom));
+    funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+    if total != funds {
+        return Err(ContractError::InvalidPrizeFunds {});
+    }
+
+    season.standings = season_standings(deps.storage, season.number)?;
+    season.ended_at = Some(env.block.time);
+    season.prizes = season
+        .standings
+        .iter()
+        .zip(prizes.iter())
+        .map(|(standing, amount)| Prize {
+            player: standing.player.clone(),
+            amount: amount.clone(),
+        })
+        .collect();
+
+    //the live scores stay behind under the old season number
+    CURRENT_SEASON.remove(deps.storage);
+    SEASONS.save(deps.storage, season.number, &season)?;
+
+    let mut messages: Vec<BankMsg> = season
+        .prizes
+        .iter()
+        .map(|prize| BankMsg::Send {
+            to_address: prize.player.to_string(),
+            amount: vec![prize.amount.clone()],
+        })
+        .collect();
+
+    //prizes nobody placed for go back to the admin
+    let unclaimed: Vec<Coin> = prizes.into_iter().skip(season.prizes.len()).collect();
+    if !unclaimed.is_empty() {
+        messages.push(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: unclaimed,
+        });
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_event(admin_event(
+            "end_season",
+            &info.sender,
+            Some(season.number.to_string()),
+        ))
+        .add_attribute("method", "try_end_season")
+        .add_attribute("season", season.number.to_string()))
+}
+
+//most wins first, then fewest losses
+fn season_standings(storage: &dyn Storage, season: u64) -> StdResult<Vec<Standing>> {
+    SEASON_RANKS
+        .sub_prefix(season)
+        .keys(storage, None, None, Order::Descending)
+        .take(SEASON_ARCHIVE_SIZE)
+        .map(|item| {
+            let (_, player) = item?;
+            Ok(Standing {
+                score: SEASON_SCORES.load(storage, (season, &player))?,
+                stats: PLAYER_STATS.may_load(storage, &player)?.unwrap_or_default(),
+                player,
+            })
+        })
+        .collect()
+}
+
+fn season_rank(score: &SeasonScore) -> Vec<u8> {
+    let mut rank = score.wins.to_be_bytes().to_vec();
+    rank.extend_from_slice(&(u64::MAX - score.losses).to_be_bytes());
+    rank
+}
+
+fn update_season_scores(storage: &mut dyn Storage, record: &GameRecord) -> StdResult<()> {
+    let season = match CURRENT_SEASON.may_load(storage)? {
+        Some(season) if record.ranked => season.number,
+        _ => return Ok(()),
+    };
+
+    for (player, win) in [
+        (&record.host, GameResult::HostWins),
+        (&record.opponent, GameResult::OpponentWins),
+    ] {
+        let mut score = SEASON_SCORES
+            .may_load(storage, (season, player))?
+            .unwrap_or_default();
+        SEASON_RANKS.remove(storage, (season, season_rank(&score), player));
+        if record.result == GameResult::Tie {
+            score.ties += 1;
+        } else if record.result == win {
+            score.wins += 1;
+        } else {
+            score.losses += 1;
+        }
+        SEASON_SCORES.save(storage, (season, player), &score)?;
+        SEASON_RANKS.save(storage, (season, season_rank(&score), player), &Empty {})?;
+    }
+
+    Ok(())
+}
+
+//the winner of a finished round is its champion
+fn round_trophy(storage: &dyn Storage, env: &Env, round: &Round) -> StdResult<Option<SubMsg>> {
+    let winner = match (&round.phase, &round.winner) {
+        (RoundPhase::Finished, Some(winner)) => winner,
+        _ => return Ok(None),
+    };
+
+    let metadata = TrophyMetadata {
+        kind: TrophyKind::Championship,
+        game_id: round.id,
+        opponents: round
+            .players
+            .iter()
+            .filter(|p| &p.address != winner)
+            .map(|p| p.address.clone())
+            .collect(),
+        winner_move: round
+            .players
+            .iter()
+            .find(|p| &p.address == winner)
+            .and_then(|p| p.revealed),
+        opponent_move: None,
+        won_at: env.block.time,
+    };
+
+    trophy_mint(storage, format!("round-{}", round.id), winner, metadata)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        //a failing subscriber must not revert the game it is notified about
+        SUBSCRIBER_REPLY_ID => match msg.result.into_result() {
+            Ok(_) => Ok(Response::new()),
+            Err(err) => Ok(Response::new()
+                .add_attribute("method", "subscriber_failed")
+                .add_attribute("error", err)),
+        },
+        CALLBACK_REPLY_ID => match msg.result.into_result() {
+            Ok(_) => Ok(Response::new()),
+            Err(err) => Ok(Response::new()
+                .add_attribute("method", "callback_failed")
+                .add_attribute("error", err)),
+        },
+        //neither may a trophy contract this contract cannot mint on
+        TROPHY_REPLY_ID => match msg.result.into_result() {
+            Ok(_) => Ok(Response::new()),
+            Err(err) => Ok(Response::new()
+                .add_attribute("method", "trophy_failed")
+                .add_attribute("error", err)),
+        },
+        REWARD_REPLY_ID => match msg.result.into_result() {
+            Ok(_) => Ok(Response::new()),
+            Err(err) => Ok(Response::new()
+                .add_attribute("method", "reward_failed")
+                .add_attribute("error", err)),
+        },
+        id => Err(StdError::generic_err(format!("unknown reply id {}", id)).into()),
+    }
+}
+
+fn admin_event(action: &str, sender: &Addr, target: Option<String>) -> Event {
+    AdminActionEvent::new(action, sender, target).into_event()
+}
+
+// passes when the sender is the player itself or holds a live grant covering the wager
+fn check_operator(
+    deps: Deps,
+    env: &Env,
+    player: &Addr,
+    sender: &Addr,
+    wager: &Option<Coin>,
+) -> Result<(), ContractError> {
+    if player == sender {
+        return Ok(());
+    }
+
+    let grant = OPERATORS
+        .may_load(deps.storage, (player, sender))?
+        .ok_or(ContractError::Unauthorized {})?;
+
+    if grant.expires.is_expired(&env.block) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(wager) = wager {
+        match &grant.max_wager {
+            Some(max) if max.denom == wager.denom && max.amount >= wager.amount => {}
+            _ => return Err(ContractError::InvalidWager {}),
+        }
+    }
+
+    Ok(())
+}
+
+// Operators act in the name of the player that granted them by naming it in a
+// game message. Funds they attach are staked for the player and capped by the
+// grant's max_wager, payouts and refunds go to the player.
+fn operator_info(
+    deps: Deps,
+    env: &Env,
+    info: MessageInfo,
+    player: Option<Addr>,
+) -> Result<MessageInfo, ContractError> {
+    let player = match player {
+        Some(player) => deps.api.addr_validate(player.as_str())?,
+        None => return Ok(info),
+    };
+
+    let stake = match info.funds.as_slice() {
+        [] => None,
+        [stake] => Some(stake.clone()),
+        _ => return Err(ContractError::InvalidWager {}),
+    };
+    check_operator(deps, env, &player, &info.sender, &stake)?;
+
+    Ok(MessageInfo {
+        sender: player,
+        funds: info.funds,
+    })
+}
+
+pub fn try_grant_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: Addr,
+    expires: Expiration,
+    max_wager: Option<Coin>,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(operator.as_str())?;
+
+    if operator == info.sender {
+        return Err(StdError::generic_err("cannot grant operator rights to yourself").into());
+    }
+
+    if expires.is_expired(&env.block) {
+        return Err(StdError::generic_err("operator grant is already expired").into());
+    }
+
+    let grant = OperatorGrant {
+        operator: operator.clone(),
+        expires,
+        max_wager,
+    };
+    OPERATORS.save(deps.storage, (&info.sender, &operator), &grant)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_grant_operator")
+        .add_attribute("player", info.sender)
+        .add_attribute("operator", operator)
+        .add_attribute("expires", expires.to_string()))
+}
+
+pub fn try_revoke_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: Addr,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, &operator);
+    if !OPERATORS.has(deps.storage, key) {
+        return Err(ContractError::OperatorNotFound {});
+    }
+    OPERATORS.remove(deps.storage, key);
+
+    Ok(Response::new()
+        .add_attribute("method", "try_revoke_operator")
+        .add_attribute("player", info.sender)
+        .add_attribute("operator", operator))
+}
+
+//a strategy contract opts in to playing as a bot by registering itself
+pub fn try_register_bot(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    BOTS.save(deps.storage, &info.sender, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_register_bot")
+        .add_attribute("bot", info.sender))
+}
+
+pub fn try_unregister_bot(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    if !BOTS.has(deps.storage, &info.sender) {
+        return Err(ContractError::BotNotRegistered {});
+    }
+    BOTS.remove(deps.storage, &info.sender);
+
+    Ok(Response::new()
+        .add_attribute("method", "try_unregister_bot")
+        .add_attribute("bot", info.sender))
+}
+
+pub fn try_play_bot_game(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bot: Addr,
+    host_move: GameMove,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    let bot = deps.api.addr_validate(bot.as_str())?;
+    if !BOTS.has(deps.storage, &bot) {
+        return Err(ContractError::BotNotRegistered {});
+    }
+
+    let id = BOT_GAME_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    BOT_GAME_SEQ.save(deps.storage, &id)?;
+    update_global_stats(deps.storage, |stats| stats.games_started += 1)?;
+
+    //the bot is asked before it could see the host's move, bot games are never
+    //ranked so a host predicting the nonce gains nothing
+    let query = BotQueryMsg::GetMove {
+        game_id: id,
+        host: info.sender.clone(),
+        nonce: bot_nonce(&env, id),
+    };
+    let answer: BotMoveResponse = deps.querier.query_wasm_smart(&bot, &query)?;
+
+    let result = get_game_result(host_move, answer.bot_move)?;
+    let (record, hooks) = finish_game(
+        deps.storage,
+        &env,
+        &info.sender,
+        &bot,
+        (Some(host_move), Some(answer.bot_move)),
+        result,
+        None,
+        vec![],
+        true,
+    )?;
+
+    Ok(Response::new()
+        .add_submessages(hooks)
+        .add_event(record_event(GameKind::Bot, Some(id), &record))
+        .add_attribute("method", "try_play_bot_game")
+        .add_attribute("game_id", id.to_string())
+        .add_attribute("host", info.sender)
+        .add_attribute("bot", bot)
+        .add_attribute("bot_move", format!("{:?}", answer.bot_move))
+        .add_attribute("result", result_str(&result))
+        .add_attribute("record_id", record.id.to_string()))
+}
+
+//derived from the block the game is played in and the game id
+fn bot_nonce(env: &Env, game_id: u64) -> Binary {
+    let mut hasher = Sha256::new();
+    hasher.update(env.block.chain_id.as_bytes());
+    hasher.update(env.block.height.to_be_bytes());
+    hasher.update(env.block.time.nanos().to_be_bytes());
+    hasher.update(game_id.to_be_bytes());
+
+    Binary::from(hasher.finalize().as_slice())
+}
+
+pub(crate) fn check_blacklist(deps: Deps, address: &Addr) -> Result<(), ContractError> {
+    let blacklist = HOOKS.query_hooks(deps)?.hooks;
+
+    for blacklisted in blacklist {
+        if blacklisted == address.as_str() {
+            return Err(ContractError::OnTheBlacklist {});
+        }
+    }
+
+    Ok(())
+}
+
+pub fn try_start_game(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    host: Option<Addr>,
+    opponent: Addr,
+    host_move: GameMove,
+    callback: Option<GameCallback>,
+) -> Result<Response, ContractError> {
+    //an operator starts the game in the name of the player that granted it
+    let host = match host {
+        Some(host) => deps.api.addr_validate(host.as_str())?,
+        None => info.sender.clone(),
+    };
+    check_operator(deps.as_ref(), &env, &host, &info.sender, &None)?;
+
+    check_blacklist(deps.as_ref(), &host)?;
+
+    let opponent = resolve_opponent(deps.as_ref(), opponent)?;
+    let _valid_addr = deps.api.addr_validate(opponent.as_str())?;
+
+    //a bot could read a move stored in the clear, see PlayBotGame
+    if BOTS.has(deps.storage, &opponent) {
+        return Err(ContractError::BotGameRequired {});
+    }
+
+    if let Some(callback) = &callback {
+        deps.api.addr_validate(callback.contract.as_str())?;
+    }
+
+    let game_found = GAME.may_load(deps.storage, (&host, &opponent))?;
+
+    match game_found {
+        Some(_) => return Err(ContractError::OneGameAtATime {}),
+        None => {
+            let g = Game {
+                host: host.clone(),
+                opponent: opponent.clone(),
+                host_move,
+                opp_move: None,
+                result: None,
+                callback,
+                sponsors: vec![],
+            };
+
+            GAME.save(deps.storage, (&host, &opponent), &g)?;
+        }
+    };
+
+    update_global_stats(deps.storage, |stats| stats.games_started += 1)?;
+
+    let event = GameStartedEvent {
+        kind: GameKind::Direct,
+        game_id: None,
+        host: host.clone(),
+        opponent: Some(opponent.clone()),
+        stake: None,
+        fee: None,
+    };
+
+    Ok(Response::new()
+        .add_event(event.into_event())
+        .add_attribute("method", "try_start_game")
+        .add_attribute("host", host)
+        .add_attribute("opponent", opponent))
+}
+
+pub fn try_cancel_game(
+    deps: DepsMut,
+    info: MessageInfo,
+    opponent: Addr,
+) -> Result<Response, ContractError> {
+    let valid_opp = deps.api.addr_validate(opponent.as_str())?;
+
+    //only the host can cancel a game that has not been answered yet
+    let game = GAME
+        .may_load(deps.storage, (&info.sender, &valid_opp))?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    GAME.remove(deps.storage, (&info.sender, &valid_opp));
+    update_global_stats(deps.storage, |stats| stats.games_cancelled += 1)?;
+
+    let mut res = Response::new().add_messages(sponsor_refunds(&game.sponsors));
+    if let Some(callback) = game.callback.clone() {
+        res = res.add_submessage(game_callback(
+            callback,
+            &game.host,
+            &game.opponent,
+            GameOutcome::Cancelled {},
+        )?);
+    }
+
+    let event = GameCancelledEvent {
+        kind: GameKind::Direct,
+        game_id: None,
+        host: game.host,
+        opponent: Some(game.opponent),
+        stake: None,
+    };
+
+    Ok(res
+        .add_event(event.into_event())
+        .add_attribute("method", "try_cancel_game")
+        .add_attribute("host", info.sender)
+        .add_attribute("opponent", valid_opp))
+}
+
+pub fn try_sponsor_game(
+    deps: DepsMut,
+    info: MessageInfo,
+    game: SponsoredGame,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    let bonus = match info.funds.as_slice() {
+        [bonus] if !bonus.amount.is_zero() => bonus.clone(),
+        _ => return Err(ContractError::InvalidSponsorBonus {}),
+    };
+    let sponsorship = Sponsorship {
+        sponsor: info.sender.clone(),
+        bonus: bonus.clone(),
+    };
+
+    let (kind, game_id, host, opponent) = match game {
+        SponsoredGame::Direct { host, opponent } => {
+            let host = deps.api.addr_validate(host.as_str())?;
+            let opponent = deps.api.addr_validate(opponent.as_str())?;
+            let mut game = GAME
+                .may_load(deps.storage, (&host, &opponent))?
+                .ok_or(ContractError::NoGameFound {})?;
+            add_sponsorship(&mut game.sponsors, sponsorship)?;
+            GAME.save(deps.storage, (&host, &opponent), &game)?;
+            (GameKind::Direct, None, host, Some(opponent))
+        }
+        SponsoredGame::Queued { game_id } => {
+            let mut game = QUEUED_GAMES
+                .may_load(deps.storage, game_id)?
+                .ok_or(ContractError::NoGameFound {})?;
+            add_sponsorship(&mut game.sponsors, sponsorship)?;
+            QUEUED_GAMES.save(deps.storage, game_id, &game)?;
+            (
+                GameKind::Queued,
+                Some(game_id),
+                game.host,
+                Some(game.opponent),
+            )
+        }
+        SponsoredGame::Round { round_id } => {
+            let mut round = ROUNDS
+                .may_load(deps.storage, round_id)?
+                .ok_or(ContractError::NoGameFound {})?;
+            if round.phase == RoundPhase::Finished {
+                return Err(ContractError::WrongRoundPhase {
+                    phase: "Commit or Reveal".to_string(),
+                });
+            }
+            add_sponsorship(&mut round.sponsors, sponsorship)?;
+            ROUNDS.save(deps.storage, round_id, &round)?;
+            (GameKind::Round, Some(round_id), round.creator, None)
+        }
+    };
+
+    let event = GameSponsoredEvent {
+        kind,
+        game_id,
+        host,
+        opponent,
+        sponsor: info.sender.clone(),
+        bonus,
+    };
+
+    Ok(Response::new()
+        .add_event(event.into_event())
+        .add_attribute("method", "try_sponsor_game")
+        .add_attribute("sponsor", info.sender))
+}
+
+//every sponsorship is refunded with its own message so a game only takes a
+//few, a sponsor adding to its bonus keeps its slot
+fn add_sponsorship(
+    sponsors: &mut Vec<Sponsorship>,
+    sponsorship: Sponsorship,
+) -> Result<(), ContractError> {
+    if let Some(existing) = sponsors.iter_mut().find(|existing| {
+        existing.sponsor == sponsorship.sponsor && existing.bonus.denom == sponsorship.bonus.denom
+    }) {
+        existing.bonus.amount += sponsorship.bonus.amount;
+        return Ok(());
+    }
+    if sponsors.len() >= MAX_SPONSORS {
+        return Err(ContractError::TooManySponsors {});
+    }
+    sponsors.push(sponsorship);
+    Ok(())
+}
+
+//splits the sponsor bonuses between the winners, the first one gets any
+//remainder. Without winners the sponsors are refunded.
+fn sponsor_payout(sponsors: &[Sponsorship], winners: &[Addr]) -> Vec<BankMsg> {
+    if winners.is_empty() {
+        return sponsor_refunds(sponsors);
+    }
+
+    let count = Uint128::from(winners.len() as u128);
+    let mut shares: Vec<Vec<Coin>> = vec![vec![]; winners.len()];
+    for sponsorship in sponsors {
+        let share = sponsorship.bonus.amount / count;
+        let remainder = sponsorship.bonus.amount - share * count;
+        for (i, coins) in shares.iter_mut().enumerate() {
+            let amount = if i == 0 { share + remainder } else { share };
+            if !amount.is_zero() {
+                add_coin(
+                    coins,
+                    &Coin {
+                        denom: sponsorship.bonus.denom.clone(),
+                        amount,
+                    },
+                );
+            }
+        }
+    }
+
+    winners
+        .iter()
+        .zip(shares)
+        .filter(|(_, coins)| !coins.is_empty())
+        .map(|(winner, mut coins)| {
+            coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+            BankMsg::Send {
+                to_address: winner.to_string(),
+                amount: coins,
+            }
+        })
+        .collect()
+}
+
+fn sponsor_refunds(sponsors: &[Sponsorship]) -> Vec<BankMsg> {
+    sponsors
+        .iter()
+        .map(|sponsorship| BankMsg::Send {
+            to_address: sponsorship.sponsor.to_string(),
+            amount: vec![sponsorship.bonus.clone()],
+        })
+        .collect()
+}
+
+//both players win a share of a tie
+fn result_winners(result: &GameResult, host: &Addr, opponent: &Addr) -> Vec<Addr> {
+    match result {
+        GameResult::HostWins => vec![host.clone()],
+        GameResult::OpponentWins => vec![opponent.clone()],
+        GameResult::Tie => vec![host.clone(), opponent.clone()],
+    }
+}
+
+fn game_callback(
+    callback: GameCallback,
+    host: &Addr,
+    opponent: &Addr,
+    outcome: GameOutcome,
+) -> StdResult<SubMsg> {
+    let msg = GameCallbackMsg {
+        host: host.clone(),
+        opponent: opponent.clone(),
+        outcome,
+        callback: callback.msg,
+    }
+    .into_cosmos_msg(callback.contract)?;
+
+    Ok(SubMsg::reply_on_error(msg, CALLBACK_REPLY_ID))
+}
+
+pub fn try_opponent_response(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    host: Addr,
+    opponent: Addr,
+    opp_move: GameMove,
+) -> Result<Response, ContractError> {
+    //validate host and opp address
+    let api = deps.api;
+
+    let valid_host = api.addr_validate(host.as_str())?;
+    let valid_opp = api.addr_validate(opponent.as_str())?;
+
+    //the opponent answers in person or through one of its operators
+    check_operator(deps.as_ref(), &env, &valid_opp, &info.sender, &None)?;
+
+    //load game
+    let game_found = query_game_by_host_and_opponent(deps.as_ref(), valid_host, valid_opp)?;
+
+    resolve_game(deps, &env, game_found, opp_move, "try_opponent_response")
+}
+
+//the opponent's move is in, decide the game and archive it
+fn resolve_game(
+    deps: DepsMut,
+    env: &Env,
+    game: Game,
+    opp_move: GameMove,
+    method: &str,
+) -> Result<Response, ContractError> {
+    //compare host move and opp move
+    let host_move = game.host_move;
+    let result = get_game_result(host_move, opp_move)?;
+
+    //return the game result
+    let result_str = result_str(&result);
+
+    //delete the game from state
+    GAME.remove(deps.storage, (&game.host, &game.opponent));
+
+    let bonus = sponsor_payout(
+        &game.sponsors,
+        &result_winners(&result, &game.host, &game.opponent),
+    );
+
+    //keep a record of the finished game and notify subscribers
+    let (record, hooks) = finish_game(
+        deps.storage,
+        env,
+        &game.host,
+        &game.opponent,
+        (Some(host_move), Some(opp_move)),
+        result,
+        None,
+        game.sponsors,
+        false,
+    )?;
+
+    //tell the host contract about the outcome
+    let mut res = Response::new().add_messages(bonus).add_submessages(hooks);
+    if let Some(callback) = game.callback {
+        res = res.add_submessage(game_callback(
+            callback,
+            &game.host,
+            &game.opponent,
+            GameOutcome::Resolved {
+                game_id: record.id,
+                result,
+            },
+        )?);
+    }
+
+    //optional: add a leaderboard
+
+    Ok(res
+        .add_event(record_event(GameKind::Direct, None, &record))
+        .add_attribute("method", method)
+        .add_attribute("host", game.host)
+        .add_attribute("opponent", game.opponent)
+        .add_attribute("result", result_str)
+        .add_attribute("game_id", record.id.to_string()))
+}
+
+//every resolved two-party game ends here
+#[allow(clippy::too_many_arguments)]
+fn finish_game(
+    storage: &mut dyn Storage,
+    env: &Env,
+    host: &Addr,
+    opponent: &Addr,
+    moves: (Option<GameMove>, Option<GameMove>),
+    result: GameResult,
+    wager: Option<Coin>,
+    sponsors: Vec<Sponsorship>,
+    bot: bool,
+) -> StdResult<(GameRecord, Vec<SubMsg>)> {
+    let id = GAME_RECORD_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    GAME_RECORD_SEQ.save(storage, &id)?;
+
+    //a bot is run by whoever registered it, so its games never count
+    let ranked = !bot && check_ranked(storage, env, host, opponent, result, &wager)?;
+    let record = GameRecord {
+        id,
+        host: host.clone(),
+        opponent: opponent.clone(),
+        host_move: moves.0,
+        opp_move: moves.1,
+        result,
+        wager,
+        finished_at: env.block.time,
+        ranked,
+        sponsors,
+        rematch_id: None,
+        bot,
+    };
+    GAME_RECORDS.save(storage, id, &record)?;
+
+    let host_streak = update_player_stats(
+        storage,
+        host,
+        &record,
+        record.host_move,
+        GameResult::HostWins,
+    )?;
+    let opp_streak = update_player_stats(
+        storage,
+        opponent,
+        &record,
+        record.opp_move,
+        GameResult::OpponentWins,
+    )?;
+
+    update_head_to_head(storage, &record)?;
+    update_season_scores(storage, &record)?;
+    update_guild_scores(storage, env, &record)?;
+    update_global_stats(storage, |stats| stats.resolved(moves, result))?;
+
+    let reply_on_error = SUBSCRIBER_REPLY_ON_ERROR
+        .may_load(storage)?
+        .unwrap_or_default();
+    let mut hooks = SUBSCRIBERS.prepare_hooks(storage, |subscriber| {
+        let msg = GameFinishedHookMsg {
+            game_id: record.id,
+            host: record.host.clone(),
+            opponent: record.opponent.clone(),
+            host_move: record.host_move,
+            opp_move: record.opp_move,
+            result: record.result,
+            stake: record.wager.clone(),
+        }
+        .into_cosmos_msg(subscriber)?;
+
+        Ok(if reply_on_error {
+            SubMsg::reply_on_error(msg, SUBSCRIBER_REPLY_ID)
+        } else {
+            SubMsg::new(msg)
+        })
+    })?;
+
+    //both players of a ranked game with a stake earn rewards, the winner a bit more
+    if ranked_stake(&record) {
+        hooks.extend(reward_mints(
+            storage,
+            env,
+            [
+                (host, result == GameResult::HostWins),
+                (opponent, result == GameResult::OpponentWins),
+            ],
+        )?);
+    }
+
+    //the winner of a ranked game with a stake collects a trophy
+    if !ranked_stake(&record) {
+        return Ok((record, hooks));
+    }
+    let (winner, loser, winner_move, loser_move, streak) = match result {
+        GameResult::HostWins => (host, opponent, moves.0, moves.1, host_streak),
+        GameResult::OpponentWins => (opponent, host, moves.1, moves.0, opp_streak),
+        GameResult::Tie => return Ok((record, hooks)),
+    };
+    let metadata = TrophyMetadata {
+        kind: TrophyKind::Win,
+        game_id: id,
+        opponents: vec![loser.clone()],
+        winner_move,
+        opponent_move: loser_move,
+        won_at: env.block.time,
+    };
+    hooks.extend(streak_milestone(storage, winner, streak, &metadata)?);
+    hooks.extend(trophy_mint(
+        storage,
+        format!("game-{}", id),
+        winner,
+        metadata,
+    )?);
+
+    Ok((record, hooks))
+}
+
+//whether the game counts toward rewards and streak bonuses
+fn ranked_stake(record: &GameRecord) -> bool {
+    record.ranked
+        && record
+            .wager
+            .as_ref()
+            .is_some_and(|wager| !wager.amount.is_zero())
+}
+
+fn update_player_stats(
+    storage: &mut dyn Storage,
+    player: &Addr,
+    record: &GameRecord,
+    player_move: Option<GameMove>,
+    win: GameResult,
+) -> StdResult<u64> {
+    let mut stats = PLAYER_STATS.may_load(storage, player)?.unwrap_or_default();
+    let previous_streak = stats.current_streak;
+
+    stats.games_played += 1;
+    if let Some(player_move) = player_move {
+        stats.moves.add(player_move);
+    }
+    if let Some(wager) = &record.wager {
+        add_coin(&mut stats.total_wagered, wager);
+    }
+
+    //only ranked games with a stake move the streaks, they earn the bonuses
+    let counted = ranked_stake(record);
+    if record.result == GameResult::Tie {
+        stats.ties += 1;
+        let ties_break_streak = STREAK_CONFIG
+            .may_load(storage)?
+            .map(|config| config.ties_break_streak)
+            .unwrap_or(true);
+        if counted && ties_break_streak {
+            stats.current_streak = 0;
+        }
+    } else if record.result == win {
+        stats.wins += 1;
+        if counted {
+            stats.current_streak += 1;
+            stats.best_streak = stats.best_streak.max(stats.current_streak);
+        }
+        if let Some(wager) = &record.wager {
+            //the winner takes both wagers
+            add_coin(&mut stats.total_won, &double(wager));
+        }
+    } else {
+        stats.losses += 1;
+        if counted {
+            stats.current_streak = 0;
+        }
+    }
+
+    if stats.current_streak != previous_streak {
+        STREAKS.remove(storage, (previous_streak, player));
+        if stats.current_streak > 0 {
+            STREAKS.save(storage, (stats.current_streak, player), &Empty {})?;
+        }
+    }
+
+    PLAYER_STATS.save(storage, player, &stats)?;
+    Ok(stats.current_streak)
+}
+
+fn update_global_stats(
+    storage: &mut dyn Storage,
+    update: impl FnOnce(&mut GlobalStats),
+) -> StdResult<()> {
+    let mut stats = GLOBAL_STATS.may_load(storage)?.unwrap_or_default();
+    update(&mut stats);
+    GLOBAL_STATS.save(storage, &stats)
+}
+
+//both players of a queued game put in the wager, the winner takes it all
+fn double(wager: &Coin) -> Coin {
+    Coin {
+        denom: wager.denom.clone(),
+        amount: wager.amount + wager.amount,
+    }
+}
+
+fn ordered_pair<'a>(a: &'a Addr, b: &'a Addr) -> (&'a Addr, &'a Addr) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn update_head_to_head(storage: &mut dyn Storage, record: &GameRecord) -> StdResult<()> {
+    let (first, second) = ordered_pair(&record.host, &record.opponent);
+    let mut h2h = HEAD_TO_HEAD
+        .may_load(storage, (first, second))?
+        .unwrap_or_default();
+
+    let winner = match record.result {
+        GameResult::HostWins => Some(&record.host),
+        GameResult::OpponentWins => Some(&record.opponent),
+        GameResult::Tie => None,
+    };
+    match winner {
+        Some(winner) if winner == first => h2h.wins_a += 1,
+        Some(_) => h2h.wins_b += 1,
+        None => h2h.ties += 1,
+    }
+    h2h.last_game = Some(record.finished_at);
+
+    HEAD_TO_HEAD.save(storage, (first, second), &h2h)
+}
+
+//adds to the total of the same denom, keeping one entry per denom
+fn add_coin(totals: &mut Vec<Coin>, coin: &Coin) {
+    match totals.iter_mut().find(|total| total.denom == coin.denom) {
+        Some(total) => total.amount += coin.amount,
+        None => totals.push(coin.clone()),
+    }
+}
+
+fn result_str(result: &GameResult) -> &'static str {
+    match result {
+        GameResult::HostWins => "Host Wins",
+        GameResult::OpponentWins => "Opponent Wins",
+        GameResult::Tie => "Tie",
+    }
+}
+
+pub fn get_game_result(
+    host_move: GameMove,
+    opp_move: GameMove,
+) -> Result<GameResult, ContractError> {
+    if host_move == opp_move {
+        Ok(GameResult::Tie)
+    } else if host_move == GameMove::Rock && opp_move == GameMove::Scissors
+        || host_move == GameMove::Paper && opp_move == GameMove::Rock
+        || host_move == GameMove::Scissors && opp_move == GameMove::Paper
+    {
+        Ok(GameResult::HostWins)
+    } else {
+        Ok(GameResult::OpponentWins)
+    }
+}
+
+pub fn commit_move(player_move: GameMove, salt: &str) -> Binary {
+    let mut hasher = Sha256::new();
+    hasher.update(events::move_str(player_move).as_bytes());
+    hasher.update(salt.as_bytes());
+
+    Binary::from(hasher.finalize().as_slice())
+}
+
+//checks a revealed move against the commitment made to it
+pub(crate) fn verify_move(
+    player_move: GameMove,
+    salt: &str,
+    commitment: &Binary,
+) -> Result<(), ContractError> {
+    if salt.len() < MIN_SALT_LENGTH {
+        return Err(ContractError::SaltTooShort {});
+    }
+    if commit_move(player_move, salt) != *commitment {
+        return Err(ContractError::CommitmentMismatch {});
+    }
+    Ok(())
+}
+
+pub fn try_join_queue(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wager: Coin,
+    move_commitment: Binary,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    if wager.amount.is_zero() || info.funds != vec![wager.clone()] {
+        return Err(ContractError::InvalidWager {});
+    }
+
+    if move_commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    let mut refunds: Vec<BankMsg> = vec![];
+
+    //an expired entry of the sender does not keep them out of the queue
+    if let Some(id) = QUEUED_PLAYERS.may_load(deps.storage, &info.sender)? {
+        let entry = QUEUE.load(deps.storage, id)?;
+        if !entry.expires.is_expired(&env.block) {
+            return Err(ContractError::AlreadyInQueue {});
+        }
+        refunds.push(unqueue(deps.storage, entry));
+    }
+
+    //only the oldest few entries with the same wager are looked at
+    let bracket = queue_bracket(&wager);
+    let ids = QUEUE_BY_WAGER
+        .prefix(&bracket)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(MAX_QUEUE_SCAN)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut waiting: Option<QueueEntry> = None;
+    for id in ids {
+        let entry = QUEUE.load(deps.storage, id)?;
+        if entry.expires.is_expired(&env.block) {
+            refunds.push(unqueue(deps.storage, entry));
+            continue;
+        }
+
+        waiting = Some(entry);
+        break;
+    }
+
+    match waiting {
+        Some(entry) => {
+            QUEUE.remove(deps.storage, entry.id);
+            QUEUE_BY_WAGER.remove(deps.storage, (&bracket, entry.id));
+            QUEUED_PLAYERS.remove(deps.storage, &entry.player);
+
+            //both play for the smaller wager, whoever put in more gets the rest back
+            let stake = if entry.wager.amount <= wager.amount {
+                entry.wager.clone()
+            } else {
+                wager.clone()
+            };
+            for (player, paid) in [(&entry.player, &entry.wager), (&info.sender, &wager)] {
+                if paid.amount > stake.amount {
+                    refunds.push(BankMsg::Send {
+                        to_address: player.to_string(),
+                        amount: vec![Coin {
+                            denom: stake.denom.clone(),
+                            amount: paid.amount - stake.amount,
+                        }],
+                    });
+                }
+            }
+            let res = Response::new().add_messages(refunds);
+
+            let id = QUEUED_GAME_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+            QUEUED_GAME_SEQ.save(deps.storage, &id)?;
+
+            let g = QueuedGame {
+                id,
+                host: entry.player,
+                opponent: info.sender,
+                wager: Some(stake),
+                host_commitment: entry.move_commitment,
+                opp_commitment: move_commitment,
+                host_move: None,
+                opp_move: None,
+                reveal_expires: REVEAL_TIMEOUT.after(&env.block),
+                sponsors: vec![],
+            };
+            QUEUED_GAMES.save(deps.storage, id, &g)?;
+
+            update_global_stats(deps.storage, |stats| {
+                stats.games_started += 1;
+                if let Some(wager) = &g.wager {
+                    add_coin(&mut stats.volume, &double(wager));
+                }
+            })?;
+
+            let event = GameStartedEvent {
+                kind: GameKind::Queued,
+                game_id: Some(id),
+                host: g.host.clone(),
+                opponent: Some(g.opponent.clone()),
+                stake: g.wager.clone(),
+                fee: None,
+            };
+
+            Ok(res
+                .add_event(event.into_event())
+                .add_attribute("method", "try_join_queue")
+                .add_attribute("game_id", id.to_string())
+                .add_attribute("host", g.host)
+                .add_attribute("opponent", g.opponent))
+        }
+        None => {
+            let res = Response::new().add_messages(refunds);
+            let id = QUEUE_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+            QUEUE_SEQ.save(deps.storage, &id)?;
+
+            let entry = QueueEntry {
+                id,
+                player: info.sender.clone(),
+                wager,
+                move_commitment,
+                expires: QUEUE_ENTRY_TIMEOUT.after(&env.block),
+            };
+            QUEUE.save(deps.storage, id, &entry)?;
+            QUEUE_BY_WAGER.save(deps.storage, (&bracket, id), &Empty {})?;
+            QUEUED_PLAYERS.save(deps.storage, &info.sender, &id)?;
+
+            Ok(res
+                .add_attribute("method", "try_join_queue")
+                .add_attribute("queued", info.sender))
+        }
+    }
+}
+
+pub fn try_leave_queue(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let id = QUEUED_PLAYERS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotInQueue {})?;
+    let entry = QUEUE.load(deps.storage, id)?;
+
+    Ok(Response::new()
+        .add_message(unqueue(deps.storage, entry))
+        .add_attribute("method", "try_leave_queue")
+        .add_attribute("player", info.sender))
+}
+
+pub fn try_purge_queue(
+    deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_PURGE_LIMIT).min(MAX_PURGE_LIMIT) as usize;
+
+    //entries expire in the order they were queued, so the oldest go first
+    let entries = QUEUE
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut refunds: Vec<BankMsg> = vec![];
+    for (_, entry) in entries {
+        if !entry.expires.is_expired(&env.block) {
+            break;
+        }
+        refunds.push(unqueue(deps.storage, entry));
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "try_purge_queue")
+        .add_attribute("purged", refunds.len().to_string())
+        .add_messages(refunds))
+}
+
+// queue entries match entries of the same denom whose wager has as many
+// digits, e.g. 10 to 99 token
+fn queue_bracket(wager: &Coin) -> String {
+    format!("{}:{}", wager.denom, wager.amount.to_string().len())
+}
+
+//drops an entry from the queue and its indexes and refunds the wager
+fn unqueue(storage: &mut dyn Storage, entry: QueueEntry) -> BankMsg {
+    QUEUE.remove(storage, entry.id);
+    QUEUE_BY_WAGER.remove(storage, (&queue_bracket(&entry.wager), entry.id));
+    QUEUED_PLAYERS.remove(storage, &entry.player);
+
+    BankMsg::Send {
+        to_address: entry.player.to_string(),
+        amount: vec![entry.wager],
+    }
+}
+
+pub fn try_reveal_move(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    game_id: u64,
+    player_move: GameMove,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let mut game = QUEUED_GAMES
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    let (commitment, revealed) = if info.sender == game.host {
+        (&game.host_commitment, &mut game.host_move)
+    } else if info.sender == game.opponent {
+        (&game.opp_commitment, &mut game.opp_move)
+    } else {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    if revealed.is_some() {
+        return Err(ContractError::AlreadyRevealed {});
+    }
+
+    verify_move(player_move, &salt, commitment)?;
+
+    *revealed = Some(player_move);
+
+    let res = Response::new()
+        .add_attribute("method", "try_reveal_move")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("player", info.sender);
+
+    match (game.host_move, game.opp_move) {
+        (Some(host_move), Some(opp_move)) => {
+            let result = get_game_result(host_move, opp_move)?;
+            let payout = queued_game_payout(&game, &result);
+            let bonus = sponsor_payout(
+                &game.sponsors,
+                &result_winners(&result, &game.host, &game.opponent),
+            );
+
+            QUEUED_GAMES.remove(deps.storage, game_id);
+            settle_side_pool(deps.storage, game_id, Some(result))?;
+            let (record, hooks) = finish_game(
+                deps.storage,
+                &env,
+                &game.host,
+                &game.opponent,
+                (game.host_move, game.opp_move),
+                result,
+                game.wager,
+                game.sponsors,
+                false,
+            )?;
+
+            Ok(res
+                .add_messages(payout)
+                .add_messages(bonus)
+                .add_submessages(hooks)
+                .add_event(record_event(GameKind::Queued, Some(game_id), &record))
+                .add_attribute("result", result_str(&result))
+                .add_attribute("record_id", record.id.to_string()))
+        }
+        _ => {
+            QUEUED_GAMES.save(deps.storage, game_id, &game)?;
+            Ok(res)
+        }
+    }
+}
+
+pub fn try_claim_timeout(deps: DepsMut, env: Env, game_id: u64) -> Result<Response, ContractError> {
+    let game = QUEUED_GAMES
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if !game.reveal_expires.is_expired(&env.block) {
+        return Err(ContractError::RevealNotExpired {});
+    }
+
+    //a player who revealed wins against one who did not, otherwise both are refunded
+    let result = match (game.host_move, game.opp_move) {
+        (Some(_), None) => GameResult::HostWins,
+        (None, Some(_)) => GameResult::OpponentWins,
+        _ => GameResult::Tie,
+    };
+    let payout = queued_game_payout(&game, &result);
+    let bonus = sponsor_payout(
+        &game.sponsors,
+        &result_winners(&result, &game.host, &game.opponent),
+    );
+
+    //side bets only settle on games both players revealed, a forfeit voids them
+    QUEUED_GAMES.remove(deps.storage, game_id);
+    settle_side_pool(deps.storage, game_id, None)?;
+    let (record, hooks) = finish_game(
+        deps.storage,
+        &env,
+        &game.host,
+        &game.opponent,
+        (game.host_move, game.opp_move),
+        result,
+        game.wager,
+        game.sponsors,
+        false,
+    )?;
+    update_global_stats(deps.storage, |stats| stats.games_forfeited += 1)?;
+
+    Ok(Response::new()
+        .add_messages(payout)
+        .add_messages(bonus)
+        .add_submessages(hooks)
+        .add_event(record_event(GameKind::Queued, Some(game_id), &record))
+        .add_attribute("method", "try_claim_timeout")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("result", result_str(&result))
+        .add_attribute("record_id", record.id.to_string()))
+}
+
+pub fn try_place_side_bet(
+    deps: DepsMut,
+    info: MessageInfo,
+    game_id: u64,
+    outcome: GameResult,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    let game = QUEUED_GAMES
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    //a revealed move gives the outcome away, and players know their own move
+    if game.host_move.is_some() || game.opp_move.is_some() {
+        return Err(ContractError::SideBetsClosed {});
+    }
+    if info.sender == game.host || info.sender == game.opponent {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let stake = match info.funds.as_slice() {
+        [stake] if !stake.amount.is_zero() => stake,
+        _ => return Err(ContractError::InvalidSideBet {}),
+    };
+
+    let mut pool = match SIDE_POOLS.may_load(deps.storage, game_id)? {
+        Some(pool) => pool,
+        None => SidePool {
+            game_id,
+            denom: stake.denom.clone(),
+            fee_bps: SIDE_BET_FEE_BPS.may_load(deps.storage)?.unwrap_or_default(),
+            stakes: SideStakes::default(),
+            status: SidePoolStatus::Open,
+        },
+    };
+    if pool.denom != stake.denom {
+        return Err(ContractError::InvalidSideBet {});
+    }
+
+    pool.stakes.add(outcome, stake.amount);
+    SIDE_POOLS.save(deps.storage, game_id, &pool)?;
+    SIDE_BETS.update(
+        deps.storage,
+        (game_id, &info.sender),
+        |bet| -> StdResult<_> {
+            let mut bet = bet.unwrap_or_default();
+            bet.add(outcome, stake.amount);
+            Ok(bet)
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_place_side_bet")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("bettor", info.sender)
+        .add_attribute("outcome", result_str(&outcome))
+        .add_attribute("stake", stake.to_string()))
+}
+
+pub fn try_claim_side_bet(
+    deps: DepsMut,
+    info: MessageInfo,
+    game_id: u64,
+) -> Result<Response, ContractError> {
+    let mut pool = SIDE_POOLS
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoSideBet {})?;
+    let bet = SIDE_BETS
+        .may_load(deps.storage, (game_id, &info.sender))?
+        .ok_or(ContractError::NoSideBet {})?;
+
+    //winners share the pool after the fee in proportion to their stakes
+    let amount = match &mut pool.status {
+        SidePoolStatus::Open => return Err(ContractError::SideBetsNotSettled {}),
+        SidePoolStatus::Void => bet.total(),
+        SidePoolStatus::Settled {
+            result,
+            fee,
+            claimed,
+            paid,
+        } => {
+            let won = bet.on(*result);
+            let winnings = pool.stakes.total() - *fee;
+            let amount = won.multiply_ratio(winnings, pool.stakes.on(*result));
+            *claimed += won;
+            *paid += amount;
+
+            //the last winner to claim leaves the rounding to the treasury
+            let remainder = winnings - *paid;
+            if !won.is_zero() && *claimed == pool.stakes.on(*result) && !remainder.is_zero() {
+                TREASURY.update(deps.storage, &pool.denom, |treasury| -> StdResult<_> {
+                    Ok(treasury.unwrap_or_default() + remainder)
+                })?;
+            }
+            amount
+        }
+    };
+
+    SIDE_POOLS.save(deps.storage, game_id, &pool)?;
+    SIDE_BETS.remove(deps.storage, (game_id, &info.sender));
+
+    let mut res = Response::new()
+        .add_attribute("method", "try_claim_side_bet")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("bettor", info.sender.clone())
+        .add_attribute("payout", amount.to_string());
+    if !amount.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: pool.denom,
+                amount,
+            }],
+        });
+    }
+
+    Ok(res)
+}
+
+pub fn try_update_side_bet_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee_bps: u16,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if fee_bps > 10_000 {
+        return Err(ContractError::InvalidSideBetFee {});
+    }
+
+    SIDE_BET_FEE_BPS.save(deps.storage, &fee_bps)?;
+
+    Ok(Response::new()
+        .add_event(admin_event(
+            "update_side_bet_fee",
+            &info.sender,
+            Some(fee_bps.to_string()),
+        ))
+        .add_attribute("method", "try_update_side_bet_fee"))
+}
+
+//settles the side pool of a finished queued game, the fee goes to the treasury
+fn settle_side_pool(
+    storage: &mut dyn Storage,
+    game_id: u64,
+    result: Option<GameResult>,
+) -> StdResult<()> {
+    let mut pool = match SIDE_POOLS.may_load(storage, game_id)? {
+        Some(pool) => pool,
+        None => return Ok(()),
+    };
+
+    pool.status = match result {
+        Some(result) if !pool.stakes.on(result).is_zero() => {
+            let fee = pool.stakes.total().multiply_ratio(pool.fee_bps, 10_000u128);
+            if !fee.is_zero() {
+                TREASURY.update(storage, &pool.denom, |treasury| -> StdResult<_> {
+                    Ok(treasury.unwrap_or_default() + fee)
+                })?;
+            }
+            SidePoolStatus::Settled {
+                result,
+                fee,
+                claimed: Uint128::zero(),
+                paid: Uint128::zero(),
+            }
+        }
+        //nobody backed the result
+        _ => SidePoolStatus::Void,
+    };
+
+    SIDE_POOLS.save(storage, game_id, &pool)
+}
+
+// resolved event for games that leave a finished game record behind
+fn record_event(kind: GameKind, game_id: Option<u64>, record: &GameRecord) -> Event {
+    GameResolvedEvent {
+        kind,
+        game_id,
+        record_id: Some(record.id),
+        host: record.host.clone(),
+        opponent: Some(record.opponent.clone()),
+        host_move: record.host_move,
+        opp_move: record.opp_move,
+        result: record.result,
+        stake: record.wager.clone(),
+        fee: None,
+    }
+    .into_event()
+}
+
+fn queued_game_payout(game: &QueuedGame, result: &GameResult) -> Vec<BankMsg> {
+    let wager = match &game.wager {
+        Some(wager) => wager,
+        None => return vec![],
+    };
+    let pot = Coin {
+        denom: wager.denom.clone(),
+        amount: wager.amount + wager.amount,
+    };
+
+    let payout = |to: &Addr, amount: Coin| BankMsg::Send {
+        to_address: to.to_string(),
+        amount: vec![amount],
+    };
+
+    match result {
+        GameResult::HostWins => vec![payout(&game.host, pot)],
+        GameResult::OpponentWins => vec![payout(&game.opponent, pot)],
+        GameResult::Tie => vec![
+            payout(&game.host, wager.clone()),
+            payout(&game.opponent, wager.clone()),
+        ],
+    }
+}
+
+pub(crate) fn check_wager_funds(
+    info: &MessageInfo,
+    wager: &Option<Coin>,
+) -> Result<(), ContractError> {
+    let expected = match wager {
+        Some(wager) => vec![wager.clone()],
+        None => vec![],
+    };
+
+    if info.funds != expected {
+        return Err(ContractError::InvalidWager {});
+    }
+
+    Ok(())
+}
+
+pub fn try_rematch(
+    deps: DepsMut,
+    info: MessageInfo,
+    game_id: u64,
+    move_commitment: Binary,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    let record = GAME_RECORDS
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if info.sender != record.host && info.sender != record.opponent {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if REMATCH_OFFERS.has(deps.storage, game_id) {
+        return Err(ContractError::RematchAlreadyOffered {});
+    }
+    if record.rematch_id.is_some() {
+        return Err(ContractError::AlreadyRematched {});
+    }
+
+    if move_commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    check_wager_funds(&info, &record.wager)?;
+
+    let offer = RematchOffer {
+        game_id,
+        proposer: info.sender.clone(),
+        move_commitment,
+    };
+    REMATCH_OFFERS.save(deps.storage, game_id, &offer)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_rematch")
+        .add_attribute("record_id", game_id.to_string())
+        .add_attribute("proposer", info.sender))
+}
+
+pub fn try_accept_rematch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    game_id: u64,
+    move_commitment: Binary,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    let mut record = GAME_RECORDS
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+    let offer = REMATCH_OFFERS
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoRematchOffer {})?;
+
+    //only the other participant of the finished game can accept
+    let accepting = if offer.proposer == record.host {
+        &record.opponent
+    } else {
+        &record.host
+    };
+    if info.sender != *accepting {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if move_commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    check_wager_funds(&info, &record.wager)?;
+
+    //roles are swapped for the rematch
+    let (host_commitment, opp_commitment) = if offer.proposer == record.opponent {
+        (offer.move_commitment, move_commitment)
+    } else {
+        (move_commitment, offer.move_commitment)
+    };
+
+    let id = QUEUED_GAME_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    QUEUED_GAME_SEQ.save(deps.storage, &id)?;
+
+    record.rematch_id = Some(id);
+    GAME_RECORDS.save(deps.storage, game_id, &record)?;
+
+    let g = QueuedGame {
+        id,
+        host: record.opponent,
+        opponent: record.host,
+        wager: record.wager,
+        host_commitment,
+        opp_commitment,
+        host_move: None,
+        opp_move: None,
+        reveal_expires: REVEAL_TIMEOUT.after(&env.block),
+        sponsors: vec![],
+    };
+    QUEUED_GAMES.save(deps.storage, id, &g)?;
+    REMATCH_OFFERS.remove(deps.storage, game_id);
+
+    update_global_stats(deps.storage, |stats| {
+        stats.games_started += 1;
+        if let Some(wager) = &g.wager {
+            add_coin(&mut stats.volume, &double(wager));
+        }
+    })?;
+
+    let event = GameStartedEvent {
+        kind: GameKind::Queued,
+        game_id: Some(id),
+        host: g.host.clone(),
+        opponent: Some(g.opponent.clone()),
+        stake: g.wager.clone(),
+        fee: None,
+    };
+
+    Ok(Response::new()
+        .add_event(event.into_event())
+        .add_attribute("method", "try_accept_rematch")
+        .add_attribute("record_id", game_id.to_string())
+        .add_attribute("game_id", id.to_string())
+        .add_attribute("host", g.host)
+        .add_attribute("opponent", g.opponent))
+}
+
+pub fn try_cancel_rematch(
+    deps: DepsMut,
+    info: MessageInfo,
+    game_id: u64,
+) -> Result<Response, ContractError> {
+    let offer = REMATCH_OFFERS
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoRematchOffer {})?;
+
+    if info.sender != offer.proposer {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let record = GAME_RECORDS.load(deps.storage, game_id)?;
+    REMATCH_OFFERS.remove(deps.storage, game_id);
+
+    let mut res = Response::new();
+    if let Some(wager) = record.wager {
+        res = res.add_message(BankMsg::Send {
+            to_address: offer.proposer.to_string(),
+            amount: vec![wager],
+        });
+    }
+
+    Ok(res
+        .add_attribute("method", "try_cancel_rematch")
+        .add_attribute("record_id", game_id.to_string()))
+}
+
+pub fn try_update_house_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    drand_pubkey: Binary,
+    drand_genesis: u64,
+    drand_period: u64,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if drand_period == 0 {
+        return Err(StdError::generic_err("drand period must be greater than zero").into());
+    }
+
+    let config = HouseConfig {
+        drand_pubkey,
+        drand_genesis,
+        drand_period,
+    };
+    HOUSE.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_event(admin_event("update_house_config", &info.sender, None))
+        .add_attribute("method", "try_update_house_config"))
+}
+
+pub fn try_fund_house(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    for fund in &info.funds {
+        HOUSE_BANKROLL.update(deps.storage, &fund.denom, |bankroll| -> StdResult<_> {
+            Ok(bankroll.unwrap_or_default() + fund.amount)
+        })?;
+    }
+
+    Ok(Response::new()
+        .add_event(admin_event("fund_house", &info.sender, None))
+        .add_attribute("method", "try_fund_house"))
+}
+
+pub fn try_withdraw_house(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Coin,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    HOUSE_BANKROLL.update(deps.storage, &amount.denom, |bankroll| {
+        bankroll
+            .unwrap_or_default()
+            .checked_sub(amount.amount)
+            .map_err(|_| ContractError::InsufficientBankroll {})
+    })?;
+
+    Ok(Response::new()
+        .add_event(admin_event(
+            "withdraw_house",
+            &info.sender,
+            Some(amount.to_string()),
+        ))
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![amount],
+        })
+        .add_attribute("method", "try_withdraw_house"))
+}
+
+pub fn try_play_house(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    player_move: GameMove,
+    round: u64,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    let config = HOUSE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HouseNotConfigured {})?;
+
+    let wager = match info.funds.as_slice() {
+        [wager] if !wager.amount.is_zero() => wager.clone(),
+        _ => return Err(ContractError::InvalidWager {}),
+    };
+
+    let published = check_drand_round(&config, &env, round)?;
+
+    //reserve the house side of the pot until the beacon arrives
+    HOUSE_BANKROLL.update(deps.storage, &wager.denom, |bankroll| {
+        bankroll
+            .unwrap_or_default()
+            .checked_sub(wager.amount)
+            .map_err(|_| ContractError::InsufficientBankroll {})
+    })?;
+
+    let id = HOUSE_GAME_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    HOUSE_GAME_SEQ.save(deps.storage, &id)?;
+
+    let g = HouseGame {
+        id,
+        player: info.sender.clone(),
+        player_move,
+        wager,
+        round,
+        beacon_expires: Expiration::AtTime(Timestamp::from_seconds(
+            published.saturating_add(HOUSE_BEACON_TIMEOUT),
+        )),
+    };
+    HOUSE_GAMES.save(deps.storage, id, &g)?;
+    update_global_stats(deps.storage, |stats| {
+        stats.games_started += 1;
+        add_coin(&mut stats.volume, &g.wager);
+    })?;
+
+    let event = GameStartedEvent {
+        kind: GameKind::House,
+        game_id: Some(id),
+        host: g.player,
+        opponent: None,
+        stake: Some(g.wager),
+        fee: None,
+    };
+
+    Ok(Response::new()
+        .add_event(event.into_event())
+        .add_attribute("method", "try_play_house")
+        .add_attribute("game_id", id.to_string())
+        .add_attribute("player", info.sender)
+        .add_attribute("round", round.to_string()))
+}
+
+pub fn try_submit_beacon(
+    deps: DepsMut,
+    game_id: u64,
+    previous_signature: Binary,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    let config = HOUSE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HouseNotConfigured {})?;
+
+    let game = HOUSE_GAMES
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if !verify_beacon(
+        &config.drand_pubkey,
+        game.round,
+        &previous_signature,
+        &signature,
+    ) {
+        return Err(ContractError::InvalidBeacon {});
+    }
+
+    let house_move = derive_move(&signature);
+    let result = get_game_result(game.player_move, house_move)?;
+
+    let pot = game.wager.amount + game.wager.amount;
+    let (player_payout, house_payout) = match result {
+        GameResult::HostWins => (pot, Uint128::zero()),
+        GameResult::OpponentWins => (Uint128::zero(), pot),
+        GameResult::Tie => (game.wager.amount, game.wager.amount),
+    };
+
+    HOUSE_BANKROLL.update(
+        deps.storage,
+        &game.wager.denom,
+        |bankroll| -> StdResult<_> { Ok(bankroll.unwrap_or_default() + house_payout) },
+    )?;
+    HOUSE_GAMES.remove(deps.storage, game_id);
+    update_global_stats(deps.storage, |stats| {
+        stats.resolved((Some(game.player_move), Some(house_move)), result)
+    })?;
+
+    //the house plays the opponent side, it has no address of its own
+    let event = GameResolvedEvent {
+        kind: GameKind::House,
+        game_id: Some(game_id),
+        record_id: None,
+        host: game.player.clone(),
+        opponent: None,
+        host_move: Some(game.player_move),
+        opp_move: Some(house_move),
+        result,
+        stake: Some(game.wager.clone()),
+        fee: None,
+    };
+
+    let mut res = Response::new().add_event(event.into_event());
+    if !player_payout.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: game.player.to_string(),
+            amount: vec![Coin {
+                denom: game.wager.denom,
+                amount: player_payout,
+            }],
+        });
+    }
+
+    Ok(res
+        .add_attribute("method", "try_submit_beacon")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("player", game.player)
+        .add_attribute("house_move", format!("{:?}", house_move))
+        .add_attribute("result", result_str(&result)))
+}
+
+//drand always publishes the beacon, a player who did not submit it in time
+//likely saw it lose, so the house takes the pot
+pub fn try_claim_house_forfeit(
+    deps: DepsMut,
+    env: Env,
+    game_id: u64,
+) -> Result<Response, ContractError> {
+    let game = HOUSE_GAMES
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if !game.beacon_expires.is_expired(&env.block) {
+        return Err(ContractError::BeaconNotExpired {});
+    }
+
+    let pot = game.wager.amount + game.wager.amount;
+    HOUSE_BANKROLL.update(
+        deps.storage,
+        &game.wager.denom,
+        |bankroll| -> StdResult<_> { Ok(bankroll.unwrap_or_default() + pot) },
+    )?;
+    HOUSE_GAMES.remove(deps.storage, game_id);
+    update_global_stats(deps.storage, |stats| {
+        stats.resolved((Some(game.player_move), None), GameResult::OpponentWins);
+        stats.games_forfeited += 1;
+    })?;
+
+    let event = GameResolvedEvent {
+        kind: GameKind::House,
+        game_id: Some(game_id),
+        record_id: None,
+        host: game.player.clone(),
+        opponent: None,
+        host_move: Some(game.player_move),
+        opp_move: None,
+        result: GameResult::OpponentWins,
+        stake: Some(game.wager.clone()),
+        fee: None,
+    };
+
+    Ok(Response::new()
+        .add_event(event.into_event())
+        .add_attribute("method", "try_claim_house_forfeit")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("player", game.player))
+}
+
+//when drand publishes the beacon of a round
+// the beacon of the chosen round must be safely unpublished, but not too far off
+fn check_drand_round(config: &HouseConfig, env: &Env, round: u64) -> Result<u64, ContractError> {
+    let now = env.block.time.seconds();
+    let published = drand_round_time(config, round);
+    if published < now.saturating_add(HOUSE_ROUND_MARGIN) {
+        return Err(ContractError::RoundNotInFuture {});
+    }
+    if published > now.saturating_add(MAX_HOUSE_ROUND_DELAY) {
+        return Err(ContractError::RoundTooFar {});
+    }
+
+    Ok(published)
+}
+
+fn drand_round_time(config: &HouseConfig, round: u64) -> u64 {
+    config
+        .drand_genesis
+        .saturating_add(round.saturating_sub(1).saturating_mul(config.drand_period))
+}
+
+pub fn get_round_result(moves: &[GameMove]) -> Result<RoundResult, ContractError> {
+    let mut distinct: Vec<GameMove> = vec![];
+    for player_move in moves {
+        if !distinct.contains(player_move) {
+            distinct.push(*player_move);
+        }
+    }
+
+    match distinct.as_slice() {
+        [first, second] => match get_game_result(*first, *second)? {
+            GameResult::HostWins => Ok(RoundResult::Eliminate {
+                winning_move: *first,
+            }),
+            GameResult::OpponentWins => Ok(RoundResult::Eliminate {
+                winning_move: *second,
+            }),
+            GameResult::Tie => Ok(RoundResult::Replay),
+        },
+        _ => Ok(RoundResult::Replay),
+    }
+}
+
+pub fn try_create_round(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    players: Vec<Addr>,
+    stake: Option<Coin>,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    let mut round_players: Vec<RoundPlayer> = vec![];
+    for player in players {
+        let address = deps.api.addr_validate(player.as_str())?;
+        if round_players.iter().all(|p| p.address != address) {
+            round_players.push(RoundPlayer {
+                address,
+                paid: false,
+                eliminated: false,
+                commitment: None,
+                revealed: None,
+            });
+        }
+    }
+
+    if round_players.len() < 2 {
+        return Err(ContractError::NotEnoughPlayers {});
+    }
+
+    if let Some(stake) = &stake {
+        if stake.amount.is_zero() {
+            return Err(ContractError::InvalidWager {});
+        }
+    }
+
+    let id = ROUND_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    ROUND_SEQ.save(deps.storage, &id)?;
+
+    let round = Round {
+        id,
+        creator: info.sender.clone(),
+        stake,
+        pot: Uint128::zero(),
+        players: round_players,
+        round_number: 1,
+        phase: RoundPhase::Commit,
+        phase_expires: ROUND_PHASE_TIMEOUT.after(&env.block),
+        winner: None,
+        sponsors: vec![],
+    };
+    ROUNDS.save(deps.storage, id, &round)?;
+
+    let event = GameStartedEvent {
+        kind: GameKind::Round,
+        game_id: Some(id),
+        host: round.creator,
+        opponent: None,
+        stake: round.stake,
+        fee: None,
+    };
+
+    Ok(Response::new()
+        .add_event(event.into_event())
+        .add_attribute("method", "try_create_round")
+        .add_attribute("round_id", id.to_string())
+        .add_attribute("creator", info.sender))
+}
+
+fn load_round_in_phase(
+    deps: Deps,
+    env: &Env,
+    round_id: u64,
+    phase: RoundPhase,
+) -> Result<Round, ContractError> {
+    let round = ROUNDS
+        .may_load(deps.storage, round_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if round.phase != phase {
+        return Err(ContractError::WrongRoundPhase {
+            phase: format!("{:?}", phase),
+        });
+    }
+
+    if round.phase_expires.is_expired(&env.block) {
+        return Err(ContractError::PhaseExpired {});
+    }
+
+    Ok(round)
+}
+
+pub fn try_commit_round_move(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round_id: u64,
+    move_commitment: Binary,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    let mut round = load_round_in_phase(deps.as_ref(), &env, round_id, RoundPhase::Commit)?;
+
+    let stake = round.stake.clone();
+    let player = round
+        .players
+        .iter_mut()
+        .find(|p| p.address == info.sender && !p.eliminated)
+        .ok_or(ContractError::Unauthorized {})?;
+
+    if player.commitment.is_some() {
+        return Err(ContractError::AlreadyCommitted {});
+    }
+
+    if move_commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    //the stake is only paid once, replays are free
+    if player.paid {
+        check_wager_funds(&info, &None)?;
+    } else {
+        check_wager_funds(&info, &stake)?;
+        player.paid = true;
+        if let Some(stake) = &stake {
+            round.pot += stake.amount;
+        }
+    }
+    player.commitment = Some(move_commitment);
+
+    if round
+        .players
+        .iter()
+        .all(|p| p.eliminated || p.commitment.is_some())
+    {
+        round.phase = RoundPhase::Reveal;
+        round.phase_expires = ROUND_PHASE_TIMEOUT.after(&env.block);
+    }
+
+    ROUNDS.save(deps.storage, round_id, &round)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_commit_round_move")
+        .add_attribute("round_id", round_id.to_string())
+        .add_attribute("player", info.sender))
+}
+
+pub fn try_reveal_round_move(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    round_id: u64,
+    player_move: GameMove,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let mut round = load_round_in_phase(deps.as_ref(), &env, round_id, RoundPhase::Reveal)?;
+
+    let player = round
+        .players
+        .iter_mut()
+        .find(|p| p.address == info.sender && !p.eliminated)
+        .ok_or(ContractError::Unauthorized {})?;
+
+    if player.revealed.is_some() {
+        return Err(ContractError::AlreadyRevealed {});
+    }
+
+    let commitment = player
+        .commitment
+        .as_ref()
+        .ok_or(ContractError::CommitmentMismatch {})?;
+    verify_move(player_move, &salt, commitment)?;
+    player.revealed = Some(player_move);
+
+    let mut payout: Vec<BankMsg> = vec![];
+    if round
+        .players
+        .iter()
+        .all(|p| p.eliminated || p.revealed.is_some())
+    {
+        payout = settle_round(&mut round, &env)?;
+    }
+
+    ROUNDS.save(deps.storage, round_id, &round)?;
+    let trophy = round_trophy(deps.storage, &env, &round)?;
+
+    Ok(Response::new()
+        .add_events(round_event(&round))
+        .add_messages(payout)
+        .add_submessages(trophy)
+        .add_attribute("method", "try_reveal_round_move")
+        .add_attribute("round_id", round_id.to_string())
+        .add_attribute("player", info.sender)
+        .add_attribute("round_number", round.round_number.to_string()))
+}
+
+pub fn try_claim_round_timeout(
+    deps: DepsMut,
+    env: Env,
+    round_id: u64,
+) -> Result<Response, ContractError> {
+    let mut round = ROUNDS
+        .may_load(deps.storage, round_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if round.phase == RoundPhase::Finished {
+        return Err(ContractError::WrongRoundPhase {
+            phase: "Commit or Reveal".to_string(),
+        });
+    }
+
+    if !round.phase_expires.is_expired(&env.block) {
+        return Err(ContractError::PhaseNotExpired {});
+    }
+
+    //players who did not act in time are eliminated
+    let in_commit = round.phase == RoundPhase::Commit;
+    let active: Vec<Addr> = round
+        .players
+        .iter()
+        .filter(|p| !p.eliminated && p.paid)
+        .map(|p| p.address.clone())
+        .collect();
+    for player in round.players.iter_mut().filter(|p| !p.eliminated) {
+        if (in_commit && player.commitment.is_none()) || (!in_commit && player.revealed.is_none()) {
+            player.eliminated = true;
+        }
+    }
+
+    let remaining = remaining_players(&round);
+    let payout = match remaining.len() {
+        //nobody acted, the players still in share the pot
+        0 => finish_round(&mut round, &active, None),
+        1 => finish_round(&mut round, &remaining, remaining.first().cloned()),
+        _ if in_commit => {
+            round.phase = RoundPhase::Reveal;
+            round.phase_expires = ROUND_PHASE_TIMEOUT.after(&env.block);
+            vec![]
+        }
+        _ => settle_round(&mut round, &env)?,
+    };
+
+    ROUNDS.save(deps.storage, round_id, &round)?;
+    let trophy = round_trophy(deps.storage, &env, &round)?;
+
+    Ok(Response::new()
+        .add_events(round_event(&round))
+        .add_messages(payout)
+        .add_submessages(trophy)
+        .add_attribute("method", "try_claim_round_timeout")
+        .add_attribute("round_id", round_id.to_string()))
+}
+
+// resolved event once a round is finished, a shared pot is a tie
+fn round_event(round: &Round) -> Option<Event> {
+    if round.phase != RoundPhase::Finished {
+        return None;
+    }
+
+    let result = match &round.winner {
+        Some(winner) if *winner == round.creator => GameResult::HostWins,
+        Some(_) => GameResult::OpponentWins,
+        None => GameResult::Tie,
+    };
+
+    let event = GameResolvedEvent {
+        kind: GameKind::Round,
+        game_id: Some(round.id),
+        record_id: None,
+        host: round.creator.clone(),
+        opponent: round.winner.clone(),
+        host_move: None,
+        opp_move: None,
+        result,
+        stake: round.stake.clone(),
+        fee: None,
+    };
+    Some(event.into_event())
+}
+
+fn remaining_players(round: &Round) -> Vec<Addr> {
+    round
+        .players
+        .iter()
+        .filter(|p| !p.eliminated)
+        .map(|p| p.address.clone())
+        .collect()
+}
+
+//resolves a round once every remaining player revealed
+fn settle_round(round: &mut Round, env: &Env) -> Result<Vec<BankMsg>, ContractError> {
+    let moves: Vec<GameMove> = round
+        .players
+        .iter()
+        .filter(|p| !p.eliminated)
+        .filter_map(|p| p.revealed)
+        .collect();
+
+    if let RoundResult::Eliminate { winning_move } = get_round_result(&moves)? {
+        for player in round.players.iter_mut().filter(|p| !p.eliminated) {
+            if player.revealed != Some(winning_move) {
+                player.eliminated = true;
+            }
+        }
+    }
+
+    let remaining = remaining_players(round);
+    if remaining.len() == 1 {
+        return Ok(finish_round(round, &remaining, remaining.first().cloned()));
+    }
+
+    for player in round.players.iter_mut() {
+        player.commitment = None;
+        player.revealed = None;
+    }
+    round.round_number += 1;
+    round.phase = RoundPhase::Commit;
+    round.phase_expires = ROUND_PHASE_TIMEOUT.after(&env.block);
+
+    Ok(vec![])
+}
+
+//splits the pot and the sponsor bonuses between the given players, the first
+//one gets any remainder
+fn finish_round(round: &mut Round, paid_to: &[Addr], winner: Option<Addr>) -> Vec<BankMsg> {
+    round.phase = RoundPhase::Finished;
+    round.winner = winner;
+
+    let bonus = sponsor_payout(&round.sponsors, paid_to);
+    let stake = match &round.stake {
+        Some(stake) if !round.pot.is_zero() && !paid_to.is_empty() => stake,
+        _ => return bonus,
+    };
+
+    let share = round.pot / Uint128::from(paid_to.len() as u128);
+    let remainder = round.pot - share * Uint128::from(paid_to.len() as u128);
+
+    paid_to
+        .iter()
+        .enumerate()
+        .map(|(i, address)| BankMsg::Send {
+            to_address: address.to_string(),
+            amount: vec![Coin {
+                denom: stake.denom.clone(),
+                amount: if i == 0 { share + remainder } else { share },
+            }],
+        })
+        .chain(bonus)
+        .collect()
+}
+
+pub fn try_update_hill_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    challenge_fee: Coin,
+    defenses_to_cash_out: u32,
+    cash_out_after: u64,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if challenge_fee.amount.is_zero() {
+        return Err(ContractError::InvalidWager {});
+    }
+
+    //the pot is kept in the fee denom, so it cannot change while fees are in it
+    let hill = HILL.may_load(deps.storage)?.unwrap_or_default();
+    if let Some(config) = HILL_CONFIG.may_load(deps.storage)? {
+        if !hill.pot.is_zero() && config.challenge_fee.denom != challenge_fee.denom {
+            return Err(StdError::generic_err(
+                "cannot change the fee denom while the pot is not empty",
+            )
+            .into());
+        }
+    }
+
+    let config = HillConfig {
+        challenge_fee,
+        defenses_to_cash_out,
+        cash_out_after,
+    };
+    HILL_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_event(admin_event("update_hill_config", &info.sender, None))
+        .add_attribute("method", "try_update_hill_config"))
+}
+
+pub fn try_claim_hill(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    move_commitment: Binary,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    HILL_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HillNotConfigured {})?;
+
+    let mut hill = HILL.may_load(deps.storage)?.unwrap_or_default();
+    if hill.champion.is_some() {
+        return Err(ContractError::HillOccupied {});
+    }
+
+    if move_commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    hill.champion = Some(info.sender.clone());
+    hill.champion_commitment = Some(move_commitment);
+    hill.crowned_at = env.block.time;
+    hill.defenses = 0;
+    HILL.save(deps.storage, &hill)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "try_claim_hill")
+        .add_attribute("champion", info.sender))
+}
+
+pub fn try_commit_hill_move(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    move_commitment: Binary,
+) -> Result<Response, ContractError> {
+    let mut hill = HILL.may_load(deps.storage)?.unwrap_or_default();
+    if hill.champion.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    //only a champion crowned by a challenge commits here, the move is locked until
+    //the next defense so it cannot be swapped when a challenge shows up in the mempool
+    if hill.champion_commitment.is_some() {
+        return Err(ContractError::CommitmentLocked {});
+    }
+
+    if move_commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    //the cash out window only runs while the champion can be challenged
+    hill.champion_commitment = Some(move_commitment);
+    hill.crowned_at = env.block.time;
+    HILL.save(deps.storage, &hill)?;
+
+    Ok(Response::new().add_attribute("method", "try_commit_hill_move"))
+}
+
+pub fn try_challenge_hill(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    challenger_move: GameMove,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    let config = HILL_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HillNotConfigured {})?;
+
+    let mut hill = HILL.may_load(deps.storage)?.unwrap_or_default();
+    match &hill.champion {
+        Some(champion) if *champion == info.sender => return Err(ContractError::Unauthorized {}),
+        Some(_) => {}
+        None => return Err(ContractError::NoGameFound {}),
+    }
+
+    if hill.champion_commitment.is_none() {
+        return Err(ContractError::ChampionNotReady {});
+    }
+
+    if hill.challenge.is_some() {
+        return Err(ContractError::ChallengePending {});
+    }
+
+    check_wager_funds(&info, &Some(config.challenge_fee.clone()))?;
+
+    hill.pot += config.challenge_fee.amount;
+    hill.challenge = Some(HillChallenge {
+        challenger: info.sender.clone(),
+        challenger_move,
+        expires: HILL_DEFENSE_TIMEOUT.after(&env.block),
+    });
+    HILL.save(deps.storage, &hill)?;
+    update_global_stats(deps.storage, |stats| {
+        stats.games_started += 1;
+        add_coin(&mut stats.fees, &config.challenge_fee);
+    })?;
+
+    let event = GameStartedEvent {
+        kind: GameKind::Hill,
+        game_id: None,
+        host: hill.champion.unwrap_or_else(|| info.sender.clone()),
+        opponent: Some(info.sender.clone()),
+        stake: None,
+        fee: Some(config.challenge_fee),
+    };
+
+    Ok(Response::new()
+        .add_event(event.into_event())
+        .add_attribute("method", "try_challenge_hill")
+        .add_attribute("challenger", info.sender))
+}
+
+pub fn try_defend_hill(
+    deps: DepsMut,
+    info: MessageInfo,
+    player_move: GameMove,
+    salt: String,
+    next_commitment: Binary,
+) -> Result<Response, ContractError> {
+    let mut hill = HILL.may_load(deps.storage)?.unwrap_or_default();
+    let champion = match &hill.champion {
+        Some(champion) if *champion == info.sender => champion.clone(),
+        _ => return Err(ContractError::Unauthorized {}),
+    };
+
+    let challenge = hill.challenge.take().ok_or(ContractError::NoChallenge {})?;
+
+    let commitment = hill
+        .champion_commitment
+        .as_ref()
+        .ok_or(ContractError::CommitmentMismatch {})?;
+    verify_move(player_move, &salt, commitment)?;
+
+    if next_commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    //the champion plays the host side of a regular game
+    let result = get_game_result(player_move, challenge.challenger_move)?;
+    match result {
+        GameResult::HostWins => {
+            hill.defenses += 1;
+            hill.champion_commitment = Some(next_commitment);
+        }
+        GameResult::Tie => {
+            hill.champion_commitment = Some(next_commitment);
+        }
+        GameResult::OpponentWins => crown(&mut hill, challenge.challenger.clone()),
+    }
+    HILL.save(deps.storage, &hill)?;
+    update_global_stats(deps.storage, |stats| {
+        stats.resolved((Some(player_move), Some(challenge.challenger_move)), result)
+    })?;
+
+    let event = hill_event(
+        deps.as_ref(),
+        &champion,
+        &challenge,
+        Some(player_move),
+        result,
+    )?;
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "try_defend_hill")
+        .add_attribute("champion", champion)
+        .add_attribute("challenger", challenge.challenger)
+        .add_attribute("result", result_str(&result)))
+}
+
+pub fn try_claim_hill_timeout(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut hill = HILL.may_load(deps.storage)?.unwrap_or_default();
+    let challenge = hill.challenge.take().ok_or(ContractError::NoChallenge {})?;
+
+    if !challenge.expires.is_expired(&env.block) {
+        return Err(ContractError::RevealNotExpired {});
+    }
+
+    //a champion who does not defend in time forfeits the seat
+    let champion = hill
+        .champion
+        .clone()
+        .unwrap_or_else(|| challenge.challenger.clone());
+    crown(&mut hill, challenge.challenger.clone());
+    HILL.save(deps.storage, &hill)?;
+    update_global_stats(deps.storage, |stats| {
+        stats.resolved(
+            (None, Some(challenge.challenger_move)),
+            GameResult::OpponentWins,
+        );
+        stats.games_forfeited += 1;
+    })?;
+
+    let event = hill_event(
+        deps.as_ref(),
+        &champion,
+        &challenge,
+        None,
+        GameResult::OpponentWins,
+    )?;
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "try_claim_hill_timeout")
+        .add_attribute("champion", challenge.challenger))
+}
+
+// the champion plays the host side, the challenge fee is reported as the fee
+fn hill_event(
+    deps: Deps,
+    champion: &Addr,
+    challenge: &HillChallenge,
+    champion_move: Option<GameMove>,
+    result: GameResult,
+) -> StdResult<Event> {
+    let config = HILL_CONFIG.load(deps.storage)?;
+
+    Ok(GameResolvedEvent {
+        kind: GameKind::Hill,
+        game_id: None,
+        record_id: None,
+        host: champion.clone(),
+        opponent: Some(challenge.challenger.clone()),
+        host_move: champion_move,
+        opp_move: Some(challenge.challenger_move),
+        result,
+        stake: None,
+        fee: Some(config.challenge_fee),
+    }
+    .into_event())
+}
+
+//the new champion is not challengeable until committing with CommitHillMove
+fn crown(hill: &mut Hill, champion: Addr) {
+    hill.champion = Some(champion);
+    hill.champion_commitment = None;
+    hill.defenses = 0;
+}
+
+pub fn try_cash_out_hill(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = HILL_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HillNotConfigured {})?;
+
+    let hill = HILL.may_load(deps.storage)?.unwrap_or_default();
+    if hill.champion.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if hill.challenge.is_some() {
+        return Err(ContractError::ChallengePending {});
+    }
+
+    //a champion who never committed a move never stood a challenge
+    let window_passed = hill.champion_commitment.is_some()
+        && env.block.time.seconds()
+            >= hill
+                .crowned_at
+                .seconds()
+                .saturating_add(config.cash_out_after);
+    if hill.defenses < config.defenses_to_cash_out && !window_passed {
+        return Err(ContractError::CannotCashOut {});
+    }
+
+    //cashing out vacates the seat
+    HILL.save(deps.storage, &Hill::default())?;
+
+    let mut res = Response::new();
+    if !hill.pot.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: config.challenge_fee.denom,
+                amount: hill.pot,
+            }],
+        });
+    }
+
+    Ok(res
+        .add_attribute("method", "try_cash_out_hill")
+        .add_attribute("champion", info.sender)
+        .add_attribute("pot", hill.pot))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetGamesByHost { host } => to_binary(&query_games_by_host(deps, host)?),
+        QueryMsg::GetGamesByOpponent { opponent } => {
+            to_binary(&query_games_by_opponent(deps, opponent)?)
+        }
+        QueryMsg::GetGameByHostAndOpponent { host, opponent } => {
+            to_binary(&query_game_by_host_and_opponent(deps, host, opponent)?)
+        }
+        QueryMsg::GetAdmin {} => to_binary(&ADMIN.get(deps)?),
+        QueryMsg::GetState {} => to_binary(&STATE.load(deps.storage)?),
+        QueryMsg::GetQueue {} => to_binary(&query_queue(deps)?),
+        QueryMsg::GetQueuedGame { game_id } => {
+            to_binary(&QUEUED_GAMES.load(deps.storage, game_id)?)
+        }
+        QueryMsg::GetSidePool { game_id } => to_binary(&SIDE_POOLS.load(deps.storage, game_id)?),
+        QueryMsg::GetSideBet { game_id, bettor } => to_binary(
+            &SIDE_BETS
+                .may_load(deps.storage, (game_id, &bettor))?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::GetHouse {} => to_binary(&query_house(deps)?),
+        QueryMsg::GetGameRecord { game_id } => {
+            to_binary(&GAME_RECORDS.load(deps.storage, game_id)?)
+        }
+        QueryMsg::GetSubscribers {} => to_binary(&SUBSCRIBERS.query_hooks(deps)?),
+        QueryMsg::GetChannel { channel_id } => to_binary(&CHANNELS.load(deps.storage, channel_id)?),
+        QueryMsg::ListIbcChannels {} => to_binary(&IbcChannelsResponse {
+            channels: query_ibc_channels(deps)?,
+        }),
+        QueryMsg::GetIbcGame {
+            channel_id,
+            game_id,
+        } => to_binary(&IBC_GAMES.load(deps.storage, (&channel_id, game_id))?),
+        QueryMsg::ListOperators { player } => to_binary(&query_operators(deps, player)?),
+        QueryMsg::ListBots {} => to_binary(&query_bots(deps)?),
+        QueryMsg::GetPlayerStats { player } => to_binary(
+            &PLAYER_STATS
+                .may_load(deps.storage, &player)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::GetHeadToHead { a, b } => to_binary(&query_head_to_head(deps, a, b)?),
+        QueryMsg::GetGlobalStats {} => {
+            to_binary(&GLOBAL_STATS.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::GetSeason { number } => to_binary(&query_season(deps, number)?),
+        QueryMsg::GetStreaks { limit } => to_binary(&query_streaks(deps, limit)?),
+        QueryMsg::GetGuild { guild_id } => to_binary(&GUILDS.load(deps.storage, guild_id)?),
+        QueryMsg::GetGuildStandings {} => to_binary(&query_guild_standings(deps)?),
+        QueryMsg::GetGuildWar { war_id } => to_binary(&GUILD_WARS.load(deps.storage, war_id)?),
+        QueryMsg::GetProfile { player } => to_binary(
+            &PROFILES
+                .may_load(deps.storage, &player)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::ResolveNickname { nickname } => to_binary(&ResolveNicknameResponse {
+            address: NICKNAMES.may_load(deps.storage, &nickname.to_lowercase())?,
+        }),
+        QueryMsg::GetRankedConfig {} => to_binary(&RANKED_CONFIG.may_load(deps.storage)?),
+        QueryMsg::GetStreakConfig {} => to_binary(&STREAK_CONFIG.may_load(deps.storage)?),
+        QueryMsg::GetTreasury {} => to_binary(&query_treasury(deps)?),
+        QueryMsg::GetRewardConfig {} => to_binary(&REWARD_CONFIG.may_load(deps.storage)?),
+        QueryMsg::GetRewardAllowance { player } => {
+            to_binary(&query_reward_allowance(deps, env, player)?)
+        }
+        QueryMsg::GetRelayNonce { player } => to_binary(&RelayNonceResponse {
+            nonce: RELAY_NONCES
+                .may_load(deps.storage, &player)?
+                .unwrap_or_default(),
+        }),
+        QueryMsg::GetHill {} => to_binary(&HillResponse {
+            config: HILL_CONFIG.may_load(deps.storage)?,
+            hill: HILL.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+        QueryMsg::GetRound { round_id } => to_binary(&ROUNDS.load(deps.storage, round_id)?),
+        QueryMsg::GetRematchOffer { game_id } => {
+            to_binary(&REMATCH_OFFERS.load(deps.storage, game_id)?)
+        }
+        QueryMsg::GetHouseGame { game_id } => to_binary(&HOUSE_GAMES.load(deps.storage, game_id)?),
+    }
+}
+
+fn query_operators(deps: Deps, player: Addr) -> StdResult<OperatorsResponse> {
+    let operators: StdResult<Vec<_>> = OPERATORS
+        .prefix(&player)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, grant)| grant))
+        .collect();
+
+    Ok(OperatorsResponse {
+        operators: operators?,
+    })
+}
+
+fn query_streaks(deps: Deps, limit: Option<u32>) -> StdResult<StreaksResponse> {
+    let limit = limit
+        .unwrap_or(DEFAULT_STREAKS_LIMIT)
+        .min(MAX_STREAKS_LIMIT) as usize;
+
+    let streaks: StdResult<Vec<_>> = STREAKS
+        .keys(deps.storage, None, None, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(streak, player)| StreakEntry { player, streak }))
+        .collect();
+
+    Ok(StreaksResponse { streaks: streaks? })
+}
+
+fn query_treasury(deps: Deps) -> StdResult<Vec<Coin>> {
+    TREASURY
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect()
+}
+
+fn query_guild_standings(deps: Deps) -> StdResult<GuildStandingsResponse> {
+    let mut guilds = GUILDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, guild)| guild))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    guilds.sort_by(|a, b| {
+        b.score
+            .wins
+            .cmp(&a.score.wins)
+            .then(a.score.losses.cmp(&b.score.losses))
+            .then(a.id.cmp(&b.id))
+    });
+
+    Ok(GuildStandingsResponse { guilds })
+}
+
+fn query_season(deps: Deps, number: Option<u64>) -> StdResult<Season> {
+    match number {
+        Some(number) => SEASONS.load(deps.storage, number),
+        None => {
+            let mut season = CURRENT_SEASON.load(deps.storage)?;
+            season.standings = season_standings(deps.storage, season.number)?;
+            Ok(season)
+        }
+    }
+}
+
+fn query_head_to_head(deps: Deps, a: Addr, b: Addr) -> StdResult<HeadToHeadResponse> {
+    let (first, second) = ordered_pair(&a, &b);
+    let record = HEAD_TO_HEAD
+        .may_load(deps.storage, (first, second))?
+        .unwrap_or_default();
+
+    let (wins_a, wins_b) = if first == &a {
+        (record.wins_a, record.wins_b)
+    } else {
+        (record.wins_b, record.wins_a)
+    };
+
+    Ok(HeadToHeadResponse {
+        a,
+        b,
+        wins_a,
+        wins_b,
+        ties: record.ties,
+        last_game: record.last_game,
+    })
+}
+
+fn query_reward_allowance(
+    deps: Deps,
+    env: Env,
+    player: Addr,
+) -> StdResult<RewardAllowanceResponse> {
+    let remaining = match REWARD_CONFIG.may_load(deps.storage)? {
+        Some(config) => config
+            .daily_cap
+            .saturating_sub(minted_today(deps.storage, &env, &player)?),
+        None => Uint128::zero(),
+    };
+
+    Ok(RewardAllowanceResponse { remaining })
+}
+
+fn query_bots(deps: Deps) -> StdResult<BotsResponse> {
+    let bots: StdResult<Vec<_>> = BOTS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect();
+
+    Ok(BotsResponse { bots: bots? })
+}
+
+fn query_house(deps: Deps) -> StdResult<HouseResponse> {
+    let bankroll: StdResult<Vec<_>> = HOUSE_BANKROLL
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect();
+
+    Ok(HouseResponse {
+        config: HOUSE.may_load(deps.storage)?,
+        bankroll: bankroll?,
+    })
+}
+
+fn query_queue(deps: Deps) -> StdResult<QueueResponse> {
+    let entries: StdResult<Vec<_>> = QUEUE
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect();
+
+    Ok(QueueResponse { entries: entries? })
+}
+
+fn query_games_by_host(deps: Deps, host: Addr) -> StdResult<GamesListResponse> {
+    let valid_addr = deps.api.addr_validate(host.as_str())?;
+
+    let all_games: StdResult<Vec<_>> = GAME
+        .prefix(&valid_addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect();
+
+    let mut found_games: Vec<Game> = vec![];
+
+    for all_games in &all_games? {
+        found_games.push(all_games.1.clone());
+    }
+
+    Ok(GamesListResponse { games: found_games })
+}
+
+fn query_games_by_opponent(deps: Deps, opponent: Addr) -> StdResult<GamesListResponse> {
+    let valid_addr = deps.api.addr_validate(opponent.as_str())?;
+
+    let all_games: StdResult<Vec<_>> = GAME
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect();
+
+    let mut found_games: Vec<Game> = vec![];
+
+    for all_games in &all_games? {
+        if all_games.1.opponent == valid_addr {
+            found_games.push(all_games.1.clone());
+        }
+    }
+
+    Ok(GamesListResponse { games: found_games })
+}
+
+fn query_game_by_host_and_opponent(deps: Deps, host: Addr, opponent: Addr) -> StdResult<Game> {
+    let valid_host = deps.api.addr_validate(host.as_str())?;
+    let valid_opp = deps.api.addr_validate(opponent.as_str())?;
+
+    let game = GAME.may_load(deps.storage, (&valid_host, &valid_opp))?;
+
+    match game {
+        Some(g) => Ok(Game {
+            host: valid_host,
+            opponent: valid_opp,
+            host_move: g.host_move,
+            opp_move: g.opp_move,
+            result: g.result,
+            callback: g.callback,
+            sponsors: g.sponsors,
+        }),
+        None => Err(StdError::generic_err("No game found")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::{GameMove, MoveCounts, PlayerStats, ResultCounts};
+
+    use super::*;
+    use crate::relay::testing::{self as relay_testing, relay_dependencies, OTHER_KEY, PLAYER_KEY};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{
+        coin, coins, from_binary, ContractResult, CosmosMsg, OwnedDeps, Querier, QuerierResult,
+        QueryRequest, ReplyOn, SubMsgResult, SystemResult, WasmMsg, WasmQuery,
+    };
+    use std::marker::PhantomData;
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        //confirm instantiation response
+        assert_eq!("owner", res.attributes[1].key);
+        assert_eq!("creator", res.attributes[1].value);
+    }
+
+    #[test]
+    fn start_game() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        //confirm instantiation response
+        assert_eq!("owner", res.attributes[1].key);
+        assert_eq!("creator", res.attributes[1].value);
+
+        // execute start game
+        let auth_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        assert_eq!("method", res.attributes[0].key);
+        assert_eq!("try_start_game", res.attributes[0].value);
+        assert_eq!("host", res.attributes[1].key);
+        assert_eq!("creator", res.attributes[1].value);
+        assert_eq!("opponent", res.attributes[2].key);
+        assert_eq!("other_player", res.attributes[2].value);
+    }
+
+    #[test]
+    fn get_games_by_host() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        //confirm instantiation response
+        assert_eq!("owner", res.attributes[1].key);
+        assert_eq!("creator", res.attributes[1].value);
+
+        // execute start game 1
+        let auth_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // execute start game 2
+        let auth_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player_2"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // execute start game 3 - other creator
+        let auth_info = mock_info("other_creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        //query game by non-host = zero games found
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByHost {
+                host: Addr::unchecked("non_host"),
+            },
+        )
+        .unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(0, value.games.len());
+
+        //query game by host = 2 games found
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByHost {
+                host: Addr::unchecked("creator"),
+            },
+        )
+        .unwrap();
+
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(2, value.games.len());
+        assert_eq!(Addr::unchecked("creator"), value.games[0].host);
+        assert_eq!(Addr::unchecked("other_player"), value.games[0].opponent);
+        assert_eq!(GameMove::Rock, value.games[0].host_move);
+        assert_eq!(None, value.games[0].opp_move);
+        assert_eq!(None, value.games[0].result);
+    }
+
+    #[test]
+    fn get_games_by_opp() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        //confirm instantiation response
+        assert_eq!("owner", res.attributes[1].key);
+        assert_eq!("creator", res.attributes[1].value);
+
+        // execute start game 1
+        let auth_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // execute start game 2
+        let auth_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player_2"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // execute start game 3 - other creator
+        let auth_info = mock_info("other_creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // execute start game 4 - another creator
+        let auth_info = mock_info("another_creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        //query game by non-opponent = zero games found
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByOpponent {
+                opponent: Addr::unchecked("non_opponent"),
+            },
+        )
+        .unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(0, value.games.len());
+
+        //query game by opponent = 3 games found
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByOpponent {
+                opponent: Addr::unchecked("other_player"),
+            },
+        )
+        .unwrap();
+
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(3, value.games.len());
+        assert_eq!(Addr::unchecked("creator"), value.games[0].host);
+        assert_eq!(Addr::unchecked("other_player"), value.games[0].opponent);
+        assert_eq!(GameMove::Rock, value.games[0].host_move);
+        assert_eq!(None, value.games[0].opp_move);
+        assert_eq!(None, value.games[0].result);
+    }
+
+    #[test]
+    fn get_game_by_host_and_opp() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        //confirm instantiation response
+        assert_eq!("owner", res.attributes[1].key);
+        assert_eq!("creator", res.attributes[1].value);
+
+        // execute start game 1
+        let auth_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // execute start game 2
+        let auth_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player_2"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // execute start game 3 - other creator
+        let auth_info = mock_info("other_creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        //query game non-players = zero games found
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGameByHostAndOpponent {
+                host: Addr::unchecked("non_host"),
+                opponent: Addr::unchecked("non_opponent"),
+            },
+        );
+
+        match res {
+            Err(_) => {}
+            _ => panic!("Should error out."),
+        }
+
+        //query game by host and opponent = 1 game found
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGameByHostAndOpponent {
+                host: Addr::unchecked("creator"),
+                opponent: Addr::unchecked("other_player"),
+            },
+        )
+        .unwrap();
+
+        let value: Game = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("creator"), value.host);
+        assert_eq!(Addr::unchecked("other_player"), value.opponent);
+        assert_eq!(GameMove::Rock, value.host_move);
+        assert_eq!(None, value.opp_move);
+        assert_eq!(None, value.result);
+    }
+
+    #[test]
+    fn update_admin() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        //confirm instantiation response
+        assert_eq!("owner", res.attributes[1].key);
+        assert_eq!("creator", res.attributes[1].value);
+
+        // execute start game 1
+        let auth_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        //query first admin
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdmin {}).unwrap();
+
+        let value: Addr = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("creator"), value);
+
+        // update admin - fail
+        let auth_info = mock_info("not_admin", &coins(2, "token"));
+        let msg = ExecuteMsg::UpdateAdmin {
+            admin: Addr::unchecked("other_admin"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg);
+
+        match res {
+            Err(_) => {}
+            _ => panic!("This is supposed to return a Contract Error"),
+        };
+
+        // update admin - success
+        let auth_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::UpdateAdmin {
+            admin: Addr::unchecked("other_admin"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        //query updated admin
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdmin {}).unwrap();
+
+        let value: Addr = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("other_admin"), value);
+    }
+
+    #[test]
+    fn blacklist() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        //confirm instantiation response
+        assert_eq!("owner", res.attributes[1].key);
+        assert_eq!("creator", res.attributes[1].value);
+
+        // add address to blacklist
+        let auth_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::AddToBlacklist {
+            address: Addr::unchecked("bad_guy"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // execute start game - fail because host is blacklisted
+        let auth_info = mock_info("bad_guy", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg);
+
+        match res {
+            Err(ContractError::OnTheBlacklist {}) => {}
+            _ => panic!("OnTheBlacklist error should occur"),
+        };
+
+        // remove address from blacklist
+        let auth_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::RemoveFromBlacklist {
+            address: Addr::unchecked("bad_guy"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // execute start game - success because host has been removed from blacklist
+        let auth_info = mock_info("bad_guy", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("other_player"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        //query game by host and opponent = 1 game found
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGameByHostAndOpponent {
+                host: Addr::unchecked("bad_guy"),
+                opponent: Addr::unchecked("other_player"),
+            },
+        )
+        .unwrap();
+
+        let value: Game = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("bad_guy"), value.host);
+        assert_eq!(Addr::unchecked("other_player"), value.opponent);
+        assert_eq!(GameMove::Rock, value.host_move);
+        assert_eq!(None, value.opp_move);
+        assert_eq!(None, value.result);
+    }
+
+    #[test]
+    fn full_game() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        //confirm instantiation response
+        assert_eq!("owner", res.attributes[1].key);
+        assert_eq!("creator", res.attributes[1].value);
+
+        // execute start game
+        let auth_info = mock_info("hosty", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("toasty"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        assert_eq!("host", res.attributes[1].key);
+        assert_eq!("hosty", res.attributes[1].value);
         assert_eq!("opponent", res.attributes[2].key);
-        assert_eq!("other_player", res.attributes[2].value);
+        assert_eq!("toasty", res.attributes[2].value);
+
+        // execute opponent reponse
+        let auth_info = mock_info("toasty", &coins(2, "token"));
+        let msg = ExecuteMsg::OpponentResponse {
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("toasty"),
+            opp_move: GameMove::Rock,
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        assert_eq!("result", res.attributes[3].key);
+        assert_eq!("Tie", res.attributes[3].value);
+
+        //query for game - fail
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGameByHostAndOpponent {
+                host: Addr::unchecked("hosty"),
+                opponent: Addr::unchecked("toasty"),
+            },
+        );
+        //confirms game data was deleted
+        match res {
+            Err(_) => {}
+            _ => panic!("No Game Found Error should occur"),
+        }
+    }
+
+    #[test]
+    fn indexer_events() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // start game - started event carries both players
+        let auth_info = mock_info("hosty", &[]);
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("toasty"),
+            host_move: GameMove::Paper,
+            callback: None,
+            host: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        let started = GameStartedEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(GameKind::Direct, started.kind);
+        assert_eq!(Addr::unchecked("hosty"), started.host);
+        assert_eq!(Some(Addr::unchecked("toasty")), started.opponent);
+
+        // opponent response - resolved event carries moves, result and record id
+        let auth_info = mock_info("toasty", &[]);
+        let msg = ExecuteMsg::OpponentResponse {
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("toasty"),
+            opp_move: GameMove::Rock,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        let resolved = GameResolvedEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(Some(1), resolved.record_id);
+        assert_eq!(Some(GameMove::Paper), resolved.host_move);
+        assert_eq!(Some(GameMove::Rock), resolved.opp_move);
+        assert_eq!(GameResult::HostWins, resolved.result);
+
+        // cancel game - cancelled event
+        let auth_info = mock_info("hosty", &[]);
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("toasty"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CancelGame {
+            opponent: Addr::unchecked("toasty"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        let cancelled = GameCancelledEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(Addr::unchecked("hosty"), cancelled.host);
+
+        // admin actions are reported with the sender and target
+        let auth_info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::AddToBlacklist {
+            address: Addr::unchecked("badguy"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        let action = AdminActionEvent::parse(&res.events[0]).unwrap();
+        assert_eq!("add_to_blacklist", action.action);
+        assert_eq!(Addr::unchecked("creator"), action.sender);
+        assert_eq!(Some("badguy".to_string()), action.target);
+    }
+
+    #[test]
+    fn matchmaking_queue() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // join queue - fail because funds do not match the wager
+        let auth_info = mock_info("hosty", &coins(5, "token"));
+        let msg = ExecuteMsg::JoinQueue {
+            wager: coin(10, "token"),
+            move_commitment: commit_move(GameMove::Rock, "hosty_secret_salt"),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg);
+
+        match res {
+            Err(ContractError::InvalidWager {}) => {}
+            _ => panic!("InvalidWager error should occur"),
+        };
+
+        // join queue - nobody waiting so the player is queued
+        let auth_info = mock_info("hosty", &coins(10, "token"));
+        let msg = ExecuteMsg::JoinQueue {
+            wager: coin(10, "token"),
+            move_commitment: commit_move(GameMove::Rock, "hosty_secret_salt"),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // join queue - different wager so the player is queued too
+        let auth_info = mock_info("whale", &coins(500, "token"));
+        let msg = ExecuteMsg::JoinQueue {
+            wager: coin(500, "token"),
+            move_commitment: commit_move(GameMove::Paper, "whale_secret_salt"),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetQueue {}).unwrap();
+        let value: QueueResponse = from_binary(&res).unwrap();
+        assert_eq!(2, value.entries.len());
+
+        // a pending direct game between the two does not hold up the queue
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("toasty"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+
+        // join queue - compatible entry waiting so a game is created
+        let auth_info = mock_info("toasty", &coins(10, "token"));
+        let msg = ExecuteMsg::JoinQueue {
+            wager: coin(10, "token"),
+            move_commitment: commit_move(GameMove::Paper, "toasty_secret_salt"),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        assert_eq!("game_id", res.attributes[1].key);
+        assert_eq!("1", res.attributes[1].value);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetQueue {}).unwrap();
+        let value: QueueResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.entries.len());
+        assert_eq!(Addr::unchecked("whale"), value.entries[0].player);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetQueuedGame { game_id: 1 },
+        )
+        .unwrap();
+        let value: QueuedGame = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("hosty"), value.host);
+        assert_eq!(Addr::unchecked("toasty"), value.opponent);
+
+        // reveal - fail because the salt does not match the commitment
+        let auth_info = mock_info("hosty", &[]);
+        let msg = ExecuteMsg::RevealMove {
+            game_id: 1,
+            player_move: GameMove::Rock,
+            salt: "wrong_secret_salt".to_string(),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg);
+
+        match res {
+            Err(ContractError::CommitmentMismatch {}) => {}
+            _ => panic!("CommitmentMismatch error should occur"),
+        };
+
+        // reveal - fail because the salt is short enough to brute force
+        let msg = ExecuteMsg::RevealMove {
+            game_id: 1,
+            player_move: GameMove::Rock,
+            salt: "short".to_string(),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg);
+
+        match res {
+            Err(ContractError::SaltTooShort {}) => {}
+            _ => panic!("SaltTooShort error should occur"),
+        };
+
+        // reveal both moves - opponent wins the pot
+        let auth_info = mock_info("hosty", &[]);
+        let msg = ExecuteMsg::RevealMove {
+            game_id: 1,
+            player_move: GameMove::Rock,
+            salt: "hosty_secret_salt".to_string(),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let auth_info = mock_info("toasty", &[]);
+        let msg = ExecuteMsg::RevealMove {
+            game_id: 1,
+            player_move: GameMove::Paper,
+            salt: "toasty_secret_salt".to_string(),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        assert_eq!("Opponent Wins", res.attributes[3].value);
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "toasty".to_string(),
+                amount: coins(20, "token"),
+            }),
+            res.messages[0].msg
+        );
+
+        // join queue - 50 is not in the bracket of 500 so shrimp waits
+        let msg = ExecuteMsg::JoinQueue {
+            wager: coin(50, "token"),
+            move_commitment: commit_move(GameMove::Rock, "shrimp_secret_salt"),
+            player: None,
+        };
+        let info = mock_info("shrimp", &coins(50, "token"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(res.messages.is_empty());
+
+        // join queue - 200 is, both play for 200 and whale gets the rest back
+        let msg = ExecuteMsg::JoinQueue {
+            wager: coin(200, "token"),
+            move_commitment: commit_move(GameMove::Rock, "dolphin_secret_salt"),
+            player: None,
+        };
+        let info = mock_info("dolphin", &coins(200, "token"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "whale".to_string(),
+                amount: coins(300, "token"),
+            })],
+            res.messages.into_iter().map(|m| m.msg).collect::<Vec<_>>()
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetQueuedGame { game_id: 2 },
+        )
+        .unwrap();
+        let value: QueuedGame = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("whale"), value.host);
+        assert_eq!(Some(coin(200, "token")), value.wager);
+
+        // leave queue - shrimp gets the wager back
+        let auth_info = mock_info("shrimp", &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            auth_info,
+            ExecuteMsg::LeaveQueue { player: None },
+        )
+        .unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "shrimp".to_string(),
+                amount: coins(50, "token"),
+            }),
+            res.messages[0].msg
+        );
+    }
+
+    #[test]
+    fn matchmaking_expiry_and_timeout() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let auth_info = mock_info("sleepy", &coins(10, "token"));
+        let msg = ExecuteMsg::JoinQueue {
+            wager: coin(10, "token"),
+            move_commitment: commit_move(GameMove::Rock, "sleepy_secret_salt"),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        let auth_info = mock_info("dozy", &coins(200, "token"));
+        let msg = ExecuteMsg::JoinQueue {
+            wager: coin(200, "token"),
+            move_commitment: commit_move(GameMove::Rock, "dozy_secret_salt"),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // sleepy's entry expired, so hosty is queued and sleepy refunded
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2 * 60 * 60);
+
+        let auth_info = mock_info("hosty", &coins(10, "token"));
+        let msg = ExecuteMsg::JoinQueue {
+            wager: coin(10, "token"),
+            move_commitment: commit_move(GameMove::Rock, "hosty_secret_salt"),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), auth_info, msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "sleepy".to_string(),
+                amount: coins(10, "token"),
+            }),
+            res.messages[0].msg
+        );
+
+        // dozy waits with a wager in another bracket, so only a purge refunds them
+        let msg = ExecuteMsg::PurgeQueue { limit: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "dozy".to_string(),
+                amount: coins(200, "token"),
+            })],
+            res.messages
+        );
+
+        let auth_info = mock_info("toasty", &coins(10, "token"));
+        let msg = ExecuteMsg::JoinQueue {
+            wager: coin(10, "token"),
+            move_commitment: commit_move(GameMove::Scissors, "toasty_secret_salt"),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), auth_info, msg).unwrap();
+
+        let auth_info = mock_info("hosty", &[]);
+        let msg = ExecuteMsg::RevealMove {
+            game_id: 1,
+            player_move: GameMove::Rock,
+            salt: "hosty_secret_salt".to_string(),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), auth_info, msg).unwrap();
+
+        // claim timeout - fail because the reveal period is still running
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 1 };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("hosty", &[]), msg);
+
+        match res {
+            Err(ContractError::RevealNotExpired {}) => {}
+            _ => panic!("RevealNotExpired error should occur"),
+        };
+
+        // claim timeout - host revealed and opponent did not
+        env.block.time = env.block.time.plus_seconds(2 * 60 * 60);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 1 };
+        let res = execute(deps.as_mut(), env, mock_info("hosty", &[]), msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "hosty".to_string(),
+                amount: coins(20, "token"),
+            }),
+            res.messages[0].msg
+        );
+    }
+
+    #[test]
+    fn house_game() {
+        use crate::drand::testing::{pubkey, sign};
+
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // play house - fail because the house is not configured
+        let auth_info = mock_info("solo", &coins(10, "token"));
+        let msg = ExecuteMsg::PlayHouse {
+            player_move: GameMove::Rock,
+            round: 20,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg);
+
+        match res {
+            Err(ContractError::HouseNotConfigured {}) => {}
+            _ => panic!("HouseNotConfigured error should occur"),
+        };
+
+        // configure the house so that round 11 is the latest published round
+        let genesis = mock_env().block.time.seconds() - 300;
+        let auth_info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::UpdateHouseConfig {
+            drand_pubkey: Binary::from(pubkey()),
+            drand_genesis: genesis,
+            drand_period: 30,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // play house - fail because the bankroll is empty
+        let auth_info = mock_info("solo", &coins(10, "token"));
+        let msg = ExecuteMsg::PlayHouse {
+            player_move: GameMove::Rock,
+            round: 20,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg);
+
+        match res {
+            Err(ContractError::InsufficientBankroll {}) => {}
+            _ => panic!("InsufficientBankroll error should occur"),
+        };
+
+        let auth_info = mock_info("creator", &coins(100, "token"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            auth_info,
+            ExecuteMsg::FundHouse {},
+        )
+        .unwrap();
+
+        // play house - fail because the round is published or too close to it
+        for round in [11, 12] {
+            let auth_info = mock_info("solo", &coins(10, "token"));
+            let msg = ExecuteMsg::PlayHouse {
+                player_move: GameMove::Rock,
+                round,
+            };
+            let res = execute(deps.as_mut(), mock_env(), auth_info, msg);
+
+            match res {
+                Err(ContractError::RoundNotInFuture {}) => {}
+                _ => panic!("RoundNotInFuture error should occur"),
+            };
+        }
+
+        // play house - fail because the round is more than an hour away
+        let auth_info = mock_info("solo", &coins(10, "token"));
+        let msg = ExecuteMsg::PlayHouse {
+            player_move: GameMove::Rock,
+            round: 200,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg);
+
+        match res {
+            Err(ContractError::RoundTooFar {}) => {}
+            _ => panic!("RoundTooFar error should occur"),
+        };
+
+        let auth_info = mock_info("solo", &coins(10, "token"));
+        let msg = ExecuteMsg::PlayHouse {
+            player_move: GameMove::Rock,
+            round: 20,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // submit beacon - fail because it is the beacon of another round
+        let previous_signature = sign(18, b"previous");
+        let msg = ExecuteMsg::SubmitBeacon {
+            game_id: 1,
+            previous_signature: Binary::from(previous_signature.clone()),
+            signature: Binary::from(sign(19, &previous_signature)),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg);
+
+        match res {
+            Err(ContractError::InvalidBeacon {}) => {}
+            _ => panic!("InvalidBeacon error should occur"),
+        };
+
+        // submit beacon - the recorded beacon of round 20 plays scissors
+        let signature = sign(20, &previous_signature);
+        assert_eq!(GameMove::Scissors, derive_move(&signature));
+        let msg = ExecuteMsg::SubmitBeacon {
+            game_id: 1,
+            previous_signature: Binary::from(previous_signature),
+            signature: Binary::from(signature),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "solo".to_string(),
+                amount: coins(20, "token"),
+            }),
+            res.messages[0].msg
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetHouse {}).unwrap();
+        let value: HouseResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(90, "token"), value.bankroll);
+
+        // nobody submits the beacon of round 21, so the house takes the pot
+        let auth_info = mock_info("solo", &coins(10, "token"));
+        let msg = ExecuteMsg::PlayHouse {
+            player_move: GameMove::Rock,
+            round: 21,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        let msg = ExecuteMsg::ClaimHouseForfeit { game_id: 2 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg);
+        match res {
+            Err(ContractError::BeaconNotExpired {}) => {}
+            _ => panic!("BeaconNotExpired error should occur"),
+        };
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(300 + 60 * 60);
+        let msg = ExecuteMsg::ClaimHouseForfeit { game_id: 2 };
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetHouse {}).unwrap();
+        let value: HouseResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(100, "token"), value.bankroll);
+    }
+
+    #[test]
+    fn rematch() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // play a full game
+        let auth_info = mock_info("hosty", &[]);
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("toasty"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        let auth_info = mock_info("toasty", &[]);
+        let msg = ExecuteMsg::OpponentResponse {
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("toasty"),
+            opp_move: GameMove::Scissors,
+        };
+        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        assert_eq!("game_id", res.attributes[4].key);
+        assert_eq!("1", res.attributes[4].value);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGameRecord { game_id: 1 },
+        )
+        .unwrap();
+        let value: GameRecord = from_binary(&res).unwrap();
+        assert_eq!(GameResult::HostWins, value.result);
+        assert_eq!(Some(GameMove::Scissors), value.opp_move);
+
+        // rematch - fail because outsiders cannot ask for a rematch
+        let msg = ExecuteMsg::Rematch {
+            game_id: 1,
+            move_commitment: commit_move(GameMove::Rock, "outsider_secret_salt"),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("outsider", &[]), msg);
+
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Unauthorized error should occur"),
+        };
+
+        // the loser asks for a rematch, the winner accepts
+        let msg = ExecuteMsg::Rematch {
+            game_id: 1,
+            move_commitment: commit_move(GameMove::Paper, "toasty_secret_salt"),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("toasty", &[]), msg).unwrap();
+
+        // accept rematch - fail because there is no such game
+        let msg = ExecuteMsg::AcceptRematch {
+            game_id: 7,
+            move_commitment: commit_move(GameMove::Paper, "hosty_secret_salt"),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg);
+
+        match res {
+            Err(ContractError::NoGameFound {}) => {}
+            _ => panic!("NoGameFound error should occur"),
+        };
+
+        let msg = ExecuteMsg::AcceptRematch {
+            game_id: 1,
+            move_commitment: commit_move(GameMove::Paper, "hosty_secret_salt"),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+        assert_eq!(
+            ("record_id", "1"),
+            (
+                res.attributes[1].key.as_str(),
+                res.attributes[1].value.as_str()
+            )
+        );
+        assert_eq!(
+            ("game_id", "1"),
+            (
+                res.attributes[2].key.as_str(),
+                res.attributes[2].value.as_str()
+            )
+        );
+
+        // rematch - fail because the game was already rematched
+        let msg = ExecuteMsg::Rematch {
+            game_id: 1,
+            move_commitment: commit_move(GameMove::Rock, "hosty_secret_salt"),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg);
+
+        match res {
+            Err(ContractError::AlreadyRematched {}) => {}
+            _ => panic!("AlreadyRematched error should occur"),
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGameRecord { game_id: 1 },
+        )
+        .unwrap();
+        let value: GameRecord = from_binary(&res).unwrap();
+        assert_eq!(Some(1), value.rematch_id);
+
+        // roles are swapped
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetQueuedGame { game_id: 1 },
+        )
+        .unwrap();
+        let value: QueuedGame = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("toasty"), value.host);
+        assert_eq!(Addr::unchecked("hosty"), value.opponent);
+        assert_eq!(None, value.wager);
+
+        let msg = ExecuteMsg::RevealMove {
+            game_id: 1,
+            player_move: GameMove::Paper,
+            salt: "hosty_secret_salt".to_string(),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::RevealMove {
+            game_id: 1,
+            player_move: GameMove::Paper,
+            salt: "toasty_secret_salt".to_string(),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("toasty", &[]), msg).unwrap();
+        assert_eq!("Tie", res.attributes[3].value);
+        assert_eq!(0, res.messages.len());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGameRecord { game_id: 2 },
+        )
+        .unwrap();
+        let value: GameRecord = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("toasty"), value.host);
+        assert_eq!(GameResult::Tie, value.result);
+    }
+
+    #[test]
+    fn round_results() {
+        use GameMove::*;
+
+        assert_eq!(
+            RoundResult::Replay,
+            get_round_result(&[Rock, Rock, Rock]).unwrap()
+        );
+        assert_eq!(
+            RoundResult::Replay,
+            get_round_result(&[Rock, Paper, Scissors, Rock]).unwrap()
+        );
+        assert_eq!(
+            RoundResult::Eliminate {
+                winning_move: Scissors
+            },
+            get_round_result(&[Paper, Scissors, Scissors, Paper]).unwrap()
+        );
+        assert_eq!(
+            RoundResult::Eliminate { winning_move: Rock },
+            get_round_result(&[Scissors, Rock]).unwrap()
+        );
+    }
+
+    #[test]
+    fn multi_player_round() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let players = ["alice", "bob", "carol"];
+
+        let msg = ExecuteMsg::CreateRound {
+            players: players.iter().map(|p| Addr::unchecked(*p)).collect(),
+            stake: Some(coin(10, "token")),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        let started = GameStartedEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(GameKind::Round, started.kind);
+        assert_eq!(Some(1), started.game_id);
+        assert_eq!(Some(coin(10, "token")), started.stake);
+
+        let play = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, moves: &[(&str, GameMove)]| {
+            let round: Round = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::GetRound { round_id: 1 },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            let salt = format!("round_secret_salt_{}", round.round_number);
+
+            for (player, player_move) in moves {
+                let funds = if round.round_number == 1 {
+                    coins(10, "token")
+                } else {
+                    vec![]
+                };
+                let msg = ExecuteMsg::CommitRoundMove {
+                    round_id: 1,
+                    move_commitment: commit_move(*player_move, &salt),
+                    player: None,
+                };
+                execute(deps.as_mut(), mock_env(), mock_info(player, &funds), msg).unwrap();
+            }
+
+            let mut res = Response::new();
+            for (player, player_move) in moves {
+                let msg = ExecuteMsg::RevealRoundMove {
+                    round_id: 1,
+                    player_move: *player_move,
+                    salt: salt.clone(),
+                    player: None,
+                };
+                res = execute(deps.as_mut(), mock_env(), mock_info(player, &[]), msg).unwrap();
+            }
+            res
+        };
+
+        // commit - fail because the stake is missing
+        let msg = ExecuteMsg::CommitRoundMove {
+            round_id: 1,
+            move_commitment: commit_move(GameMove::Rock, "long_enough_secret_salt"),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg);
+
+        match res {
+            Err(ContractError::InvalidWager {}) => {}
+            _ => panic!("InvalidWager error should occur"),
+        };
+
+        // everyone plays rock - round is replayed
+        let _res = play(
+            &mut deps,
+            &[
+                ("alice", GameMove::Rock),
+                ("bob", GameMove::Rock),
+                ("carol", GameMove::Rock),
+            ],
+        );
+
+        // carol loses with scissors against two rocks
+        let _res = play(
+            &mut deps,
+            &[
+                ("alice", GameMove::Rock),
+                ("bob", GameMove::Rock),
+                ("carol", GameMove::Scissors),
+            ],
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRound { round_id: 1 },
+        )
+        .unwrap();
+        let value: Round = from_binary(&res).unwrap();
+        assert_eq!(3, value.round_number);
+        assert_eq!(Uint128::new(30), value.pot);
+        assert!(value.players[2].eliminated);
+
+        // commit - fail because carol has been eliminated
+        let msg = ExecuteMsg::CommitRoundMove {
+            round_id: 1,
+            move_commitment: commit_move(GameMove::Rock, "long_enough_secret_salt"),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), msg);
+
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Unauthorized error should occur"),
+        };
+
+        // alice beats bob and takes the pot
+        let res = play(
+            &mut deps,
+            &[("alice", GameMove::Paper), ("bob", GameMove::Rock)],
+        );
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(30, "token"),
+            }),
+            res.messages[0].msg
+        );
+        let resolved = GameResolvedEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(Some(Addr::unchecked("alice")), resolved.opponent);
+        assert_eq!(GameResult::HostWins, resolved.result);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRound { round_id: 1 },
+        )
+        .unwrap();
+        let value: Round = from_binary(&res).unwrap();
+        assert_eq!(RoundPhase::Finished, value.phase);
+        assert_eq!(Some(Addr::unchecked("alice")), value.winner);
+    }
+
+    #[test]
+    fn king_of_the_hill() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateHillConfig {
+            challenge_fee: coin(5, "token"),
+            defenses_to_cash_out: 2,
+            cash_out_after: 24 * 60 * 60,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::ClaimHill {
+            move_commitment: commit_move(GameMove::Rock, "alice_hill_salt_1"),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+
+        // challenge - fail because the fee is missing
+        let msg = ExecuteMsg::ChallengeHill {
+            challenger_move: GameMove::Scissors,
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg);
+
+        match res {
+            Err(ContractError::InvalidWager {}) => {}
+            _ => panic!("InvalidWager error should occur"),
+        };
+
+        // alice defends twice
+        for (challenger, challenger_move, defense, salt, next) in [
+            (
+                "bob",
+                GameMove::Scissors,
+                GameMove::Rock,
+                "alice_hill_salt_1",
+                "alice_hill_salt_2",
+            ),
+            (
+                "carol",
+                GameMove::Paper,
+                GameMove::Scissors,
+                "alice_hill_salt_2",
+                "alice_hill_salt_3",
+            ),
+        ] {
+            let msg = ExecuteMsg::ChallengeHill {
+                challenger_move,
+                player: None,
+            };
+            let auth_info = mock_info(challenger, &coins(5, "token"));
+            let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+            let msg = ExecuteMsg::DefendHill {
+                player_move: defense,
+                salt: salt.to_string(),
+                next_commitment: commit_move(GameMove::Scissors, next),
+                player: None,
+            };
+            let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+            assert_eq!("Host Wins", res.attributes[3].value);
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetHill {}).unwrap();
+        let value: HillResponse = from_binary(&res).unwrap();
+        assert_eq!(2, value.hill.defenses);
+        assert_eq!(Uint128::new(10), value.hill.pot);
+
+        // alice cashes out and vacates the seat
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::CashOutHill { player: None },
+        )
+        .unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(10, "token"),
+            }),
+            res.messages[0].msg
+        );
+
+        // dave takes the empty seat but never answers erin's challenge
+        let msg = ExecuteMsg::ClaimHill {
+            move_commitment: commit_move(GameMove::Rock, "dave_hill_salt_1"),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("dave", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::ChallengeHill {
+            challenger_move: GameMove::Paper,
+            player: None,
+        };
+        let auth_info = mock_info("erin", &coins(5, "token"));
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        // cash out - fail because dave has not defended the seat yet
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("dave", &[]),
+            ExecuteMsg::CashOutHill { player: None },
+        );
+
+        match res {
+            Err(ContractError::ChallengePending {}) => {}
+            _ => panic!("ChallengePending error should occur"),
+        };
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2 * 60 * 60);
+        let msg = ExecuteMsg::ClaimHillTimeout {};
+        let _res = execute(deps.as_mut(), env, mock_info("erin", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetHill {}).unwrap();
+        let value: HillResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(Addr::unchecked("erin")), value.hill.champion);
+        assert_eq!(None, value.hill.champion_commitment);
+        assert_eq!(Uint128::new(5), value.hill.pot);
+
+        // cash out - fail because erin never committed, so nobody could challenge
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2 * 24 * 60 * 60);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("erin", &[]),
+            ExecuteMsg::CashOutHill { player: None },
+        );
+
+        match res {
+            Err(ContractError::CannotCashOut {}) => {}
+            _ => panic!("CannotCashOut error should occur"),
+        };
+
+        // erin commits a defense and cannot swap it afterwards
+        let commit = |deps: &mut OwnedDeps<_, _, _>, salt: &str| {
+            let msg = ExecuteMsg::CommitHillMove {
+                move_commitment: commit_move(GameMove::Rock, salt),
+                player: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("erin", &[]), msg)
+        };
+        commit(&mut deps, "erin_hill_salt_1").unwrap();
+        let res = commit(&mut deps, "erin_hill_salt_2");
+
+        match res {
+            Err(ContractError::CommitmentLocked {}) => {}
+            _ => panic!("CommitmentLocked error should occur"),
+        };
+
+        // a window past the end of time never opens, and does not overflow
+        let msg = ExecuteMsg::UpdateHillConfig {
+            challenge_fee: coin(5, "token"),
+            defenses_to_cash_out: 2,
+            cash_out_after: u64::MAX,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("erin", &[]),
+            ExecuteMsg::CashOutHill { player: None },
+        );
+
+        match res {
+            Err(ContractError::CannotCashOut {}) => {}
+            _ => panic!("CannotCashOut error should occur"),
+        };
+    }
+
+    #[test]
+    fn subscriber_hooks() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // add subscriber - fail because only the admin manages subscribers
+        let msg = ExecuteMsg::AddSubscriber {
+            address: Addr::unchecked("indexer"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg);
+
+        match res {
+            Err(ContractError::Hook(_)) => {}
+            _ => panic!("Hook error should occur"),
+        };
+
+        let msg = ExecuteMsg::AddSubscriber {
+            address: Addr::unchecked("indexer"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let play = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>| {
+            let msg = ExecuteMsg::StartGame {
+                opponent: Addr::unchecked("toasty"),
+                host_move: GameMove::Rock,
+                callback: None,
+                host: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::OpponentResponse {
+                host: Addr::unchecked("hosty"),
+                opponent: Addr::unchecked("toasty"),
+                opp_move: GameMove::Paper,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("toasty", &[]), msg).unwrap()
+        };
+
+        let res = play(&mut deps);
+        assert_eq!(1, res.messages.len());
+        assert_eq!(ReplyOn::Never, res.messages[0].reply_on);
+
+        let hook = GameFinishedHookMsg {
+            game_id: 1,
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("toasty"),
+            host_move: Some(GameMove::Rock),
+            opp_move: Some(GameMove::Paper),
+            result: GameResult::OpponentWins,
+            stake: None,
+        };
+        assert_eq!(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "indexer".to_string(),
+                msg: hook.into_binary().unwrap(),
+                funds: vec![],
+            }),
+            res.messages[0].msg
+        );
+
+        // in reply-on-error mode a failing subscriber is only reported
+        let msg = ExecuteMsg::UpdateSubscriberMode {
+            reply_on_error: true,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = play(&mut deps);
+        assert_eq!(ReplyOn::Error, res.messages[0].reply_on);
+        assert_eq!(SUBSCRIBER_REPLY_ID, res.messages[0].id);
+
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: SUBSCRIBER_REPLY_ID,
+                result: SubMsgResult::Err("out of gas".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!("out of gas", res.attributes[1].value);
+    }
+
+    #[test]
+    fn game_callbacks() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let callback = GameCallback {
+            contract: Addr::unchecked("dao"),
+            msg: Binary::from(b"proposal_7".to_vec()),
+        };
+
+        for opponent in ["toasty", "ghosty"] {
+            let msg = ExecuteMsg::StartGame {
+                opponent: Addr::unchecked(opponent),
+                host_move: GameMove::Rock,
+                callback: Some(callback.clone()),
+                host: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info("dao", &[]), msg).unwrap();
+        }
+
+        // resolved game calls back with the result
+        let msg = ExecuteMsg::OpponentResponse {
+            host: Addr::unchecked("dao"),
+            opponent: Addr::unchecked("toasty"),
+            opp_move: GameMove::Scissors,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("toasty", &[]), msg).unwrap();
+
+        let expected = GameCallbackMsg {
+            host: Addr::unchecked("dao"),
+            opponent: Addr::unchecked("toasty"),
+            outcome: GameOutcome::Resolved {
+                game_id: 1,
+                result: GameResult::HostWins,
+            },
+            callback: callback.msg.clone(),
+        };
+        assert_eq!(
+            SubMsg::reply_on_error(expected.into_cosmos_msg("dao").unwrap(), CALLBACK_REPLY_ID),
+            res.messages[0]
+        );
+
+        // cancel game - fail because only the host can cancel
+        let msg = ExecuteMsg::CancelGame {
+            opponent: Addr::unchecked("ghosty"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("ghosty", &[]), msg);
+
+        match res {
+            Err(ContractError::NoGameFound {}) => {}
+            _ => panic!("NoGameFound error should occur"),
+        };
+
+        // cancelled game calls back too
+        let msg = ExecuteMsg::CancelGame {
+            opponent: Addr::unchecked("ghosty"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("dao", &[]), msg).unwrap();
+
+        let expected = GameCallbackMsg {
+            host: Addr::unchecked("dao"),
+            opponent: Addr::unchecked("ghosty"),
+            outcome: GameOutcome::Cancelled {},
+            callback: callback.msg,
+        };
+        assert_eq!(
+            expected.into_cosmos_msg("dao").unwrap(),
+            res.messages[0].msg
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByHost {
+                host: Addr::unchecked("dao"),
+            },
+        )
+        .unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(0, value.games.len());
+
+        // a failing callback contract is reported through reply
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: CALLBACK_REPLY_ID,
+                result: SubMsgResult::Err("unknown variant".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!("callback_failed", res.attributes[0].value);
+    }
+
+    #[test]
+    fn trophies() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // configure the trophy contract - fail because only the admin can
+        let msg = ExecuteMsg::UpdateTrophyContract {
+            contract: Some(Addr::unchecked("trophies")),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hosty", &[]),
+            msg.clone(),
+        );
+        assert!(res.is_err());
+
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetState {}).unwrap();
+        let value: State = from_binary(&res).unwrap();
+        assert_eq!(Some(Addr::unchecked("trophies")), value.trophy_contract);
+
+        // trophies are only minted for ranked games with a stake, hosty queues
+        // first so it hosts
+        let play = |deps: &mut OwnedDeps<_, _, _>, game_id: u64, opp_move| {
+            let moves = [
+                ("hosty", GameMove::Rock, "hosty_trophy_secret_salt"),
+                ("toasty", opp_move, "toasty_trophy_secret_salt"),
+            ];
+            for (player, player_move, salt) in moves {
+                let msg = ExecuteMsg::JoinQueue {
+                    wager: coin(10, "token"),
+                    move_commitment: commit_move(player_move, salt),
+                    player: None,
+                };
+                let info = mock_info(player, &coins(10, "token"));
+                execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            }
+
+            let mut res = Response::new();
+            for (player, player_move, salt) in moves {
+                let msg = ExecuteMsg::RevealMove {
+                    game_id,
+                    player_move,
+                    salt: salt.to_string(),
+                    player: None,
+                };
+                res = execute(deps.as_mut(), mock_env(), mock_info(player, &[]), msg).unwrap();
+            }
+
+            //leave out the payouts
+            res.messages
+                .into_iter()
+                .filter(|sub| matches!(sub.msg, CosmosMsg::Wasm(_)))
+                .collect::<Vec<_>>()
+        };
+
+        // the winner gets a trophy for the game
+        let minted = play(&mut deps, 1, GameMove::Paper);
+
+        let expected = TrophyMintMsg {
+            token_id: "game-1".to_string(),
+            owner: "toasty".to_string(),
+            token_uri: None,
+            extension: TrophyMetadata {
+                kind: TrophyKind::Win,
+                game_id: 1,
+                opponents: vec![Addr::unchecked("hosty")],
+                winner_move: Some(GameMove::Paper),
+                opponent_move: Some(GameMove::Rock),
+                won_at: mock_env().block.time,
+            },
+        };
+        assert_eq!(
+            vec![SubMsg::reply_on_error(
+                expected.into_cosmos_msg("trophies").unwrap(),
+                TROPHY_REPLY_ID
+            )],
+            minted
+        );
+
+        // ties earn nothing
+        let minted = play(&mut deps, 2, GameMove::Rock);
+        assert!(minted.is_empty());
+
+        // neither do free games
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("toasty"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::OpponentResponse {
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("toasty"),
+            opp_move: GameMove::Paper,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("toasty", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+
+        // without a trophy contract nothing is minted
+        let msg = ExecuteMsg::UpdateTrophyContract { contract: None };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let minted = play(&mut deps, 3, GameMove::Scissors);
+        assert!(minted.is_empty());
+
+        // a failing mint is reported through reply
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: TROPHY_REPLY_ID,
+                result: SubMsgResult::Err("Unauthorized".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!("trophy_failed", res.attributes[0].value);
+    }
+
+    #[test]
+    fn reward_emissions() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // 10 per game and 5 more per win, doubled from tomorrow on
+        let now = mock_env().block.time;
+        let schedule = vec![
+            EmissionRate {
+                start: now,
+                per_game: Uint128::new(10),
+                per_win: Uint128::new(5),
+            },
+            EmissionRate {
+                start: now.plus_seconds(24 * 60 * 60),
+                per_game: Uint128::new(20),
+                per_win: Uint128::new(10),
+            },
+        ];
+
+        // update reward config - fail because the schedule is not sorted
+        let msg = ExecuteMsg::UpdateRewardConfig {
+            token: Addr::unchecked("rps_token"),
+            daily_cap: Uint128::new(25),
+            schedule: schedule.iter().rev().cloned().collect(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+
+        match res {
+            Err(ContractError::InvalidEmissionSchedule {}) => {}
+            _ => panic!("InvalidEmissionSchedule error should occur"),
+        }
+
+        let msg = ExecuteMsg::UpdateRewardConfig {
+            token: Addr::unchecked("rps_token"),
+            daily_cap: Uint128::new(25),
+            schedule,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // rewards are only minted for ranked games with a stake, hosty queues
+        // first so it hosts and toasty wins with paper
+        let play = |deps: &mut OwnedDeps<_, _, _>, env: Env, game_id: u64| {
+            let moves = [
+                ("hosty", GameMove::Rock, "hosty_reward_secret_salt"),
+                ("toasty", GameMove::Paper, "toasty_reward_secret_salt"),
+            ];
+            for (player, player_move, salt) in moves {
+                let msg = ExecuteMsg::JoinQueue {
+                    wager: coin(10, "token"),
+                    move_commitment: commit_move(player_move, salt),
+                    player: None,
+                };
+                let info = mock_info(player, &coins(10, "token"));
+                execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+            }
+
+            let mut res = Response::new();
+            for (player, player_move, salt) in moves {
+                let msg = ExecuteMsg::RevealMove {
+                    game_id,
+                    player_move,
+                    salt: salt.to_string(),
+                    player: None,
+                };
+                res = execute(deps.as_mut(), env.clone(), mock_info(player, &[]), msg).unwrap();
+            }
+
+            //the first message pays out the wagers
+            res.messages[1..].to_vec()
+        };
+        let mint = |recipient: &str, amount: u128| {
+            let msg = Cw20ExecuteMsg::Mint {
+                recipient: recipient.to_string(),
+                amount: Uint128::new(amount),
+            };
+            SubMsg::reply_on_error(msg.into_cosmos_msg("rps_token").unwrap(), REWARD_REPLY_ID)
+        };
+        let allowance = |deps: &OwnedDeps<_, _, _>, env: Env, player: &str| {
+            let msg = QueryMsg::GetRewardAllowance {
+                player: Addr::unchecked(player),
+            };
+            let res = query(deps.as_ref(), env, msg).unwrap();
+            let value: RewardAllowanceResponse = from_binary(&res).unwrap();
+            value.remaining.u128()
+        };
+
+        // free games earn nothing
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("toasty"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::OpponentResponse {
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("toasty"),
+            opp_move: GameMove::Paper,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("toasty", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+
+        // both players earn, the winner more
+        let res = play(&mut deps, mock_env(), 1);
+        assert_eq!(vec![mint("hosty", 10), mint("toasty", 15)], res);
+        assert_eq!(15, allowance(&deps, mock_env(), "hosty"));
+        assert_eq!(10, allowance(&deps, mock_env(), "toasty"));
+
+        // the winner hits the daily cap
+        let res = play(&mut deps, mock_env(), 2);
+        assert_eq!(vec![mint("hosty", 10), mint("toasty", 10)], res);
+
+        let res = play(&mut deps, mock_env(), 3);
+        assert_eq!(vec![mint("hosty", 5)], res);
+        assert_eq!(0, allowance(&deps, mock_env(), "hosty"));
+
+        // the next day the caps reset and the new rate applies
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(24 * 60 * 60);
+        assert_eq!(25, allowance(&deps, env.clone(), "toasty"));
+
+        let res = play(&mut deps, env, 4);
+        assert_eq!(vec![mint("hosty", 20), mint("toasty", 25)], res);
+
+        // unranked games earn nothing
+        let msg = ExecuteMsg::UpdateRankedConfig {
+            config: Some(RankedConfig {
+                max_pair_games: 10,
+                window: 60 * 60,
+                min_stake: Some(coin(100, "token")),
+                max_alternations: 2,
+            }),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2 * 24 * 60 * 60);
+        assert!(play(&mut deps, env, 5).is_empty());
+    }
+
+    #[test]
+    fn player_stats() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // a tie and a win for toasty in direct games
+        for opp_move in [GameMove::Rock, GameMove::Paper] {
+            let msg = ExecuteMsg::StartGame {
+                opponent: Addr::unchecked("toasty"),
+                host_move: GameMove::Rock,
+                callback: None,
+                host: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::OpponentResponse {
+                host: Addr::unchecked("hosty"),
+                opponent: Addr::unchecked("toasty"),
+                opp_move,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info("toasty", &[]), msg).unwrap();
+        }
+
+        // and a win of a wagered queued game
+        for (player, player_move) in [("hosty", GameMove::Rock), ("toasty", GameMove::Paper)] {
+            let msg = ExecuteMsg::JoinQueue {
+                wager: coin(10, "token"),
+                move_commitment: commit_move(player_move, &format!("{}_secret_move_salt", player)),
+                player: None,
+            };
+            let info = mock_info(player, &coins(10, "token"));
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+        for (player, player_move) in [("hosty", GameMove::Rock), ("toasty", GameMove::Paper)] {
+            let msg = ExecuteMsg::RevealMove {
+                game_id: 1,
+                player_move,
+                salt: format!("{}_secret_move_salt", player),
+                player: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(player, &[]), msg).unwrap();
+        }
+
+        let stats = |deps: &OwnedDeps<_, _, _>, player: &str| {
+            let msg = QueryMsg::GetPlayerStats {
+                player: Addr::unchecked(player),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let value: PlayerStats = from_binary(&res).unwrap();
+            value
+        };
+
+        let value = stats(&deps, "toasty");
+        assert_eq!(
+            PlayerStats {
+                games_played: 3,
+                wins: 2,
+                losses: 0,
+                ties: 1,
+                // only the wagered game counts toward the streak
+                current_streak: 1,
+                best_streak: 1,
+                total_wagered: coins(10, "token"),
+                total_won: coins(20, "token"),
+                moves: MoveCounts {
+                    rock: 1,
+                    paper: 2,
+                    scissors: 0,
+                },
+            },
+            value
+        );
+
+        let value = stats(&deps, "hosty");
+        assert_eq!(2, value.losses);
+        assert_eq!(0, value.current_streak);
+        assert!(value.total_won.is_empty());
+        assert_eq!(3, value.moves.rock);
+
+        // players without games have empty stats
+        assert_eq!(PlayerStats::default(), stats(&deps, "ghosty"));
+    }
+
+    #[test]
+    fn head_to_head() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut play = |host: &str, opponent: &str, opp_move| {
+            let msg = ExecuteMsg::StartGame {
+                opponent: Addr::unchecked(opponent),
+                host_move: GameMove::Rock,
+                callback: None,
+                host: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(host, &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::OpponentResponse {
+                host: Addr::unchecked(host),
+                opponent: Addr::unchecked(opponent),
+                opp_move,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(opponent, &[]), msg).unwrap();
+        };
+
+        // toasty wins twice, once as host, hosty once and one tie
+        play("hosty", "toasty", GameMove::Paper);
+        play("toasty", "hosty", GameMove::Scissors);
+        play("toasty", "hosty", GameMove::Paper);
+        play("hosty", "toasty", GameMove::Rock);
+
+        let h2h = |a: &str, b: &str| {
+            let msg = QueryMsg::GetHeadToHead {
+                a: Addr::unchecked(a),
+                b: Addr::unchecked(b),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let value: HeadToHeadResponse = from_binary(&res).unwrap();
+            value
+        };
+
+        let value = h2h("toasty", "hosty");
+        assert_eq!(2, value.wins_a);
+        assert_eq!(1, value.wins_b);
+        assert_eq!(1, value.ties);
+        assert_eq!(Some(mock_env().block.time), value.last_game);
+
+        // the same record the other way round
+        let value = h2h("hosty", "toasty");
+        assert_eq!(Addr::unchecked("hosty"), value.a);
+        assert_eq!(1, value.wins_a);
+        assert_eq!(2, value.wins_b);
+
+        let value = h2h("hosty", "ghosty");
+        assert_eq!(
+            (0, 0, 0, None),
+            (value.wins_a, value.wins_b, value.ties, value.last_game)
+        );
+    }
+
+    #[test]
+    fn global_stats() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let start_game = || ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("toasty"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+
+        // a cancelled and a resolved direct game
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hosty", &[]),
+            start_game(),
+        )
+        .unwrap();
+        let msg = ExecuteMsg::CancelGame {
+            opponent: Addr::unchecked("toasty"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hosty", &[]),
+            start_game(),
+        )
+        .unwrap();
+        let msg = ExecuteMsg::OpponentResponse {
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("toasty"),
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("toasty", &[]), msg).unwrap();
+
+        // a wagered queued game toasty forfeits by not revealing
+        for player in ["hosty", "toasty"] {
+            let msg = ExecuteMsg::JoinQueue {
+                wager: coin(10, "token"),
+                move_commitment: commit_move(
+                    GameMove::Scissors,
+                    &format!("{}_secret_move_salt", player),
+                ),
+                player: None,
+            };
+            let info = mock_info(player, &coins(10, "token"));
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+        let msg = ExecuteMsg::RevealMove {
+            game_id: 1,
+            player_move: GameMove::Scissors,
+            salt: "hosty_secret_move_salt".to_string(),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60 * 60 + 1);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 1 };
+        let _res = execute(deps.as_mut(), env, mock_info("hosty", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGlobalStats {}).unwrap();
+        let value: GlobalStats = from_binary(&res).unwrap();
+        assert_eq!(
+            GlobalStats {
+                games_started: 3,
+                games_resolved: 2,
+                games_cancelled: 1,
+                games_forfeited: 1,
+                results: ResultCounts {
+                    host_wins: 1,
+                    opponent_wins: 1,
+                    ties: 0,
+                },
+                moves: MoveCounts {
+                    rock: 1,
+                    paper: 1,
+                    scissors: 1,
+                },
+                volume: coins(20, "token"),
+                fees: vec![],
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn seasons() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let play = |deps: &mut OwnedDeps<_, _, _>, host: &str, opponent: &str, opp_move| {
+            let msg = ExecuteMsg::StartGame {
+                opponent: Addr::unchecked(opponent),
+                host_move: GameMove::Rock,
+                callback: None,
+                host: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(host, &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::OpponentResponse {
+                host: Addr::unchecked(host),
+                opponent: Addr::unchecked(opponent),
+                opp_move,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(opponent, &[]), msg).unwrap();
+        };
+
+        // games outside of a season do not count
+        play(&mut deps, "hosty", "toasty", GameMove::Scissors);
+
+        // start season - fail because only the admin can
+        let msg = ExecuteMsg::StartSeason {};
+        let res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg);
+        assert!(res.is_err());
+
+        let msg = ExecuteMsg::StartSeason {};
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // start season - fail because one is in progress
+        let msg = ExecuteMsg::StartSeason {};
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+
+        match res {
+            Err(ContractError::SeasonInProgress {}) => {}
+            _ => panic!("SeasonInProgress error should occur"),
+        }
+
+        // toasty wins twice, hosty once against ghosty
+        play(&mut deps, "hosty", "toasty", GameMove::Paper);
+        play(&mut deps, "ghosty", "toasty", GameMove::Paper);
+        play(&mut deps, "ghosty", "hosty", GameMove::Paper);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetSeason { number: None },
+        )
+        .unwrap();
+        let value: Season = from_binary(&res).unwrap();
+        assert_eq!(1, value.number);
+        let players: Vec<_> = value.standings.iter().map(|s| s.player.as_str()).collect();
+        assert_eq!(vec!["toasty", "hosty", "ghosty"], players);
+        assert_eq!(1, value.standings[1].score.losses);
+
+        // end season - fail because only the archived top players can place
+        let msg = ExecuteMsg::EndSeason {
+            prizes: vec![coin(1, "token"); SEASON_ARCHIVE_SIZE + 1],
+        };
+        let info = mock_info("creator", &coins(SEASON_ARCHIVE_SIZE as u128 + 1, "token"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::TooManyPrizes {}) => {}
+            _ => panic!("TooManyPrizes error should occur"),
+        }
+
+        // end season - fail because the funds do not cover the prizes
+        let msg = ExecuteMsg::EndSeason {
+            prizes: vec![coin(100, "token"), coin(50, "token")],
+        };
+        let info = mock_info("creator", &coins(100, "token"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+
+        match res {
+            Err(ContractError::InvalidPrizeFunds {}) => {}
+            _ => panic!("InvalidPrizeFunds error should occur"),
+        }
+
+        // the top two are paid
+        let info = mock_info("creator", &coins(150, "token"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "toasty".to_string(),
+                    amount: coins(100, "token"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "hosty".to_string(),
+                    amount: coins(50, "token"),
+                }),
+            ],
+            res.messages.into_iter().map(|m| m.msg).collect::<Vec<_>>()
+        );
+
+        // the next season starts from scratch, the past one stays queryable
+        let msg = ExecuteMsg::StartSeason {};
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetSeason { number: None },
+        )
+        .unwrap();
+        let value: Season = from_binary(&res).unwrap();
+        assert_eq!(2, value.number);
+        assert!(value.standings.is_empty());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetSeason { number: Some(1) },
+        )
+        .unwrap();
+        let value: Season = from_binary(&res).unwrap();
+        assert_eq!(Some(mock_env().block.time), value.ended_at);
+        assert_eq!(3, value.standings.len());
+        assert_eq!(Addr::unchecked("toasty"), value.prizes[0].player);
+        assert_eq!(2, value.standings[0].score.wins);
+
+        // the all-time stats are frozen along with the season
+        assert_eq!(3, value.standings[0].stats.games_played);
+        assert_eq!(1, value.standings[0].stats.losses);
+
+        play(&mut deps, "toasty", "hosty", GameMove::Paper);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetSeason { number: Some(1) },
+        )
+        .unwrap();
+        let value: Season = from_binary(&res).unwrap();
+        assert_eq!(3, value.standings[0].stats.games_played);
+    }
+
+    #[test]
+    fn win_streaks() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // ties keep streaks alive, two wins in a row earn 5 tokens
+        let msg = ExecuteMsg::UpdateStreakConfig {
+            ties_break_streak: false,
+            milestones: vec![StreakMilestone {
+                streak: 2,
+                bonus: coin(5, "token"),
+            }],
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let info = mock_info("creator", &coins(7, "token"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundTreasury {}).unwrap();
+
+        // streaks only count ranked games with a stake, the host queues first
+        // and plays rock
+        let play = |deps: &mut OwnedDeps<_, _, _>, host: &str, opponent: &str, opp_move| {
+            let moves = [
+                (host, GameMove::Rock, "host_streak_secret_salt"),
+                (opponent, opp_move, "opponent_streak_secret_salt"),
+            ];
+            let mut game_id = 0;
+            for (player, player_move, salt) in moves {
+                let msg = ExecuteMsg::JoinQueue {
+                    wager: coin(10, "token"),
+                    move_commitment: commit_move(player_move, salt),
+                    player: None,
+                };
+                let info = mock_info(player, &coins(10, "token"));
+                let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+                if let Some(attr) = res.attributes.iter().find(|a| a.key == "game_id") {
+                    game_id = attr.value.parse().unwrap();
+                }
+            }
+
+            let mut res = Response::new();
+            for (player, player_move, salt) in moves {
+                let msg = ExecuteMsg::RevealMove {
+                    game_id,
+                    player_move,
+                    salt: salt.to_string(),
+                    player: None,
+                };
+                res = execute(deps.as_mut(), mock_env(), mock_info(player, &[]), msg).unwrap();
+            }
+            res
+        };
+
+        // toasty wins, ties and wins again
+        play(&mut deps, "hosty", "toasty", GameMove::Paper);
+        play(&mut deps, "hosty", "toasty", GameMove::Rock);
+        let res = play(&mut deps, "hosty", "toasty", GameMove::Paper);
+        assert_eq!(
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "toasty".to_string(),
+                amount: coins(5, "token"),
+            })],
+            res.messages[1..]
+        );
+
+        // free games do not count toward streaks
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("ghosty"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::OpponentResponse {
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("ghosty"),
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("ghosty", &[]), msg).unwrap();
+
+        let msg = QueryMsg::GetPlayerStats {
+            player: Addr::unchecked("ghosty"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: PlayerStats = from_binary(&res).unwrap();
+        assert_eq!((1, 0), (value.wins, value.current_streak));
+
+        // ghosty reaches the milestone too, but the treasury is short
+        play(&mut deps, "hosty", "ghosty", GameMove::Paper);
+        let res = play(&mut deps, "hosty", "ghosty", GameMove::Paper);
+        assert_eq!(1, res.messages.len());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetTreasury {}).unwrap();
+        let value: Vec<Coin> = from_binary(&res).unwrap();
+        assert_eq!(coins(2, "token"), value);
+
+        // toasty extends the streak, hosty wins once
+        play(&mut deps, "hosty", "toasty", GameMove::Paper);
+        play(&mut deps, "toasty", "hosty", GameMove::Paper);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetStreaks { limit: None },
+        )
+        .unwrap();
+        let value: StreaksResponse = from_binary(&res).unwrap();
+        let streaks: Vec<_> = value
+            .streaks
+            .iter()
+            .map(|s| (s.player.as_str(), s.streak))
+            .collect();
+        assert_eq!(vec![("ghosty", 2), ("hosty", 1)], streaks);
+
+        let msg = QueryMsg::GetPlayerStats {
+            player: Addr::unchecked("toasty"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: PlayerStats = from_binary(&res).unwrap();
+        assert_eq!((0, 3), (value.current_streak, value.best_streak));
+
+        // withdraw treasury - fail because it holds less
+        let msg = ExecuteMsg::WithdrawTreasury {
+            amount: coin(3, "token"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+
+        match res {
+            Err(ContractError::InsufficientTreasury {}) => {}
+            _ => panic!("InsufficientTreasury error should occur"),
+        }
+    }
+
+    #[test]
+    fn ranked_limits() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // two ranked games per pair and hour, winners may flip once per hour
+        let config = RankedConfig {
+            max_pair_games: 2,
+            window: 60 * 60,
+            min_stake: None,
+            max_alternations: 2,
+        };
+        let msg = ExecuteMsg::UpdateRankedConfig {
+            config: Some(config.clone()),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::StartSeason {};
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        for (player, name) in [("hosty", "Red Clan"), ("toasty", "Blue Clan")] {
+            let msg = ExecuteMsg::CreateGuild {
+                name: name.to_string(),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(player, &[]), msg).unwrap();
+        }
+
+        let play = |deps: &mut OwnedDeps<_, _, _>, env: Env, host_move| {
+            let msg = ExecuteMsg::StartGame {
+                opponent: Addr::unchecked("toasty"),
+                host_move,
+                callback: None,
+                host: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("hosty", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::OpponentResponse {
+                host: Addr::unchecked("hosty"),
+                opponent: Addr::unchecked("toasty"),
+                opp_move: GameMove::Paper,
+            };
+            let _res = execute(deps.as_mut(), env, mock_info("toasty", &[]), msg).unwrap();
+        };
+        let ranked = |deps: &OwnedDeps<_, _, _>, game_id| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetGameRecord { game_id },
+            )
+            .unwrap();
+            let value: GameRecord = from_binary(&res).unwrap();
+            value.ranked
+        };
+
+        // the third game of the hour is played but not ranked
+        for _ in 0..3 {
+            play(&mut deps, mock_env(), GameMove::Rock);
+        }
+        assert!(ranked(&deps, 2));
+        assert!(!ranked(&deps, 3));
+
+        // the next hour hosty wins, then toasty again which looks like taking turns
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60 * 60);
+        play(&mut deps, env.clone(), GameMove::Scissors);
+        play(&mut deps, env, GameMove::Rock);
+        assert!(ranked(&deps, 4));
+        assert!(!ranked(&deps, 5));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetSeason { number: None },
+        )
+        .unwrap();
+        let value: Season = from_binary(&res).unwrap();
+        assert_eq!(2, value.standings[0].score.wins);
+        assert_eq!(1, value.standings[1].score.wins);
+
+        // the guild standings skip the unranked games as well
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGuildStandings {}).unwrap();
+        let value: GuildStandingsResponse = from_binary(&res).unwrap();
+        let scores: Vec<_> = value
+            .guilds
+            .iter()
+            .map(|guild| (guild.id, guild.score.wins, guild.score.losses))
+            .collect();
+        assert_eq!(vec![(2, 2, 1), (1, 1, 2)], scores);
+
+        // a tie between the flips does not hide them
+        let msg = ExecuteMsg::UpdateRankedConfig {
+            config: Some(RankedConfig {
+                max_pair_games: 10,
+                ..config.clone()
+            }),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2 * 60 * 60);
+        play(&mut deps, env.clone(), GameMove::Scissors);
+        play(&mut deps, env.clone(), GameMove::Paper);
+        play(&mut deps, env, GameMove::Rock);
+        assert!(ranked(&deps, 6));
+        assert!(ranked(&deps, 7));
+        assert!(!ranked(&deps, 8));
+
+        // games without the minimum stake are not ranked
+        let msg = ExecuteMsg::UpdateRankedConfig {
+            config: Some(RankedConfig {
+                min_stake: Some(coin(10, "token")),
+                ..config
+            }),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(3 * 60 * 60);
+        play(&mut deps, env, GameMove::Rock);
+        assert!(!ranked(&deps, 9));
+    }
+
+    #[test]
+    fn profiles() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let set_profile = |nickname: &str| ExecuteMsg::SetProfile {
+            nickname: Some(nickname.to_string()),
+            avatar_uri: Some("ipfs://toast".to_string()),
+            bio: None,
+        };
+
+        // set profile - fail because of the charset and length
+        for nickname in ["to", "toast y", "t0ásty"] {
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("toasty", &[]),
+                set_profile(nickname),
+            );
+
+            match res {
+                Err(ContractError::InvalidNickname {}) => {}
+                _ => panic!("InvalidNickname error should occur"),
+            }
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("toasty", &[]),
+            set_profile("Toast_1"),
+        );
+        assert!(res.is_ok());
+
+        // set profile - fail because nicknames are unique regardless of case
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hosty", &[]),
+            set_profile("TOAST_1"),
+        );
+
+        match res {
+            Err(ContractError::NicknameTaken {}) => {}
+            _ => panic!("NicknameTaken error should occur"),
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ResolveNickname {
+                nickname: "toast_1".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ResolveNicknameResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(Addr::unchecked("toasty")), value.address);
+
+        // start a game against a nickname
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("@toast_1"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+        assert_eq!("toasty", res.attributes[2].value);
+
+        let msg = ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("@ghost"),
+            host_move: GameMove::Rock,
+            callback: None,
+            host: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg);
+
+        match res {
+            Err(ContractError::NicknameNotFound {}) => {}
+            _ => panic!("NicknameNotFound error should occur"),
+        }
+
+        // the admin clears the nickname, the rest of the profile stays
+        let msg = ExecuteMsg::ClearNickname {
+            player: Addr::unchecked("toasty"),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hosty", &[]),
+            msg.clone(),
+        );
+        assert!(res.is_err());
+
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetProfile {
+                player: Addr::unchecked("toasty"),
+            },
+        )
+        .unwrap();
+        let value: Profile = from_binary(&res).unwrap();
+        assert_eq!(None, value.nickname);
+        assert_eq!(Some("ipfs://toast".to_string()), value.avatar_uri);
+
+        // the nickname is free again
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hosty", &[]),
+            set_profile("toast_1"),
+        );
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn guilds() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let run = |deps: &mut OwnedDeps<_, _, _>, sender: &str, msg: ExecuteMsg| {
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+        };
+        let play = |deps: &mut OwnedDeps<_, _, _>, host: &str, opponent: &str, opp_move| {
+            let msg = ExecuteMsg::StartGame {
+                opponent: Addr::unchecked(opponent),
+                host_move: GameMove::Rock,
+                callback: None,
+                host: None,
+            };
+            run(deps, host, msg).unwrap();
+
+            let msg = ExecuteMsg::OpponentResponse {
+                host: Addr::unchecked(host),
+                opponent: Addr::unchecked(opponent),
+                opp_move,
+            };
+            run(deps, opponent, msg).unwrap();
+        };
+
+        let create = |name: &str| ExecuteMsg::CreateGuild {
+            name: name.to_string(),
+        };
+        run(&mut deps, "hosty", create("Red Clan")).unwrap();
+        let res = run(&mut deps, "toasty", create("red clan"));
+        match res {
+            Err(ContractError::GuildNameTaken {}) => {}
+            _ => panic!("GuildNameTaken error should occur"),
+        }
+        let res = run(&mut deps, "toasty", create("x"));
+        match res {
+            Err(ContractError::InvalidGuildName {}) => {}
+            _ => panic!("InvalidGuildName error should occur"),
+        }
+        run(&mut deps, "toasty", create("Blue Clan")).unwrap();
+
+        // only the leader invites and only invited players join
+        let invite = ExecuteMsg::InviteToGuild {
+            player: Addr::unchecked("ghosty"),
+        };
+        let res = run(&mut deps, "ghosty", invite.clone());
+        match res {
+            Err(ContractError::NotInGuild {}) => {}
+            _ => panic!("NotInGuild error should occur"),
+        }
+        let res = run(
+            &mut deps,
+            "ghosty",
+            ExecuteMsg::AcceptGuildInvite { guild_id: 1 },
+        );
+        match res {
+            Err(ContractError::NoGuildInvite {}) => {}
+            _ => panic!("NoGuildInvite error should occur"),
+        }
+        run(&mut deps, "hosty", invite.clone()).unwrap();
+        run(
+            &mut deps,
+            "ghosty",
+            ExecuteMsg::AcceptGuildInvite { guild_id: 1 },
+        )
+        .unwrap();
+
+        // invite - fail because ghosty is a member already
+        let res = run(&mut deps, "hosty", invite);
+        match res {
+            Err(ContractError::AlreadyInGuild {}) => {}
+            _ => panic!("AlreadyInGuild error should occur"),
+        }
+
+        // blue declares a one hour war on red
+        let msg = ExecuteMsg::DeclareGuildWar {
+            opponent_guild: 1,
+            duration: 3600,
+        };
+        run(&mut deps, "toasty", msg).unwrap();
+        let res = run(
+            &mut deps,
+            "ghosty",
+            ExecuteMsg::AcceptGuildWar { war_id: 1 },
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Unauthorized error should occur"),
+        }
+        run(&mut deps, "hosty", ExecuteMsg::AcceptGuildWar { war_id: 1 }).unwrap();
+
+        // red wins twice, blue once, one tie and a game inside red does not count
+        play(&mut deps, "hosty", "toasty", GameMove::Scissors);
+        play(&mut deps, "toasty", "ghosty", GameMove::Paper);
+        play(&mut deps, "toasty", "hosty", GameMove::Scissors);
+        play(&mut deps, "hosty", "toasty", GameMove::Rock);
+        play(&mut deps, "hosty", "ghosty", GameMove::Paper);
+
+        // unranked games count toward neither the standings nor the war
+        let msg = ExecuteMsg::UpdateRankedConfig {
+            config: Some(RankedConfig {
+                max_pair_games: 10,
+                window: 60 * 60,
+                min_stake: Some(coin(10, "token")),
+                max_alternations: 10,
+            }),
+        };
+        run(&mut deps, "creator", msg).unwrap();
+        play(&mut deps, "hosty", "toasty", GameMove::Paper);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGuildStandings {}).unwrap();
+        let value: GuildStandingsResponse = from_binary(&res).unwrap();
+        let scores: Vec<_> = value
+            .guilds
+            .iter()
+            .map(|guild| {
+                (
+                    guild.id,
+                    guild.score.wins,
+                    guild.score.losses,
+                    guild.score.ties,
+                )
+            })
+            .collect();
+        assert_eq!(vec![(1, 2, 1, 1), (2, 1, 2, 1)], scores);
+
+        let res = run(
+            &mut deps,
+            "anyone",
+            ExecuteMsg::FinishGuildWar { war_id: 1 },
+        );
+        match res {
+            Err(ContractError::GuildWarNotOver {}) => {}
+            _ => panic!("GuildWarNotOver error should occur"),
+        }
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(3600);
+        let msg = ExecuteMsg::FinishGuildWar { war_id: 1 };
+        execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGuildWar { war_id: 1 },
+        )
+        .unwrap();
+        let war: GuildWar = from_binary(&res).unwrap();
+        assert_eq!((1, 2), (war.wins_a, war.wins_b));
+        assert_eq!(GuildWarStatus::Finished, war.status);
+        assert_eq!(Some(1), war.winner);
+
+        // the leader leaving hands red to ghosty, the last one out disbands it
+        run(&mut deps, "hosty", ExecuteMsg::LeaveGuild {}).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGuild { guild_id: 1 },
+        )
+        .unwrap();
+        let guild: Guild = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("ghosty"), guild.leader);
+
+        run(&mut deps, "ghosty", ExecuteMsg::LeaveGuild {}).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGuild { guild_id: 1 },
+        );
+        assert!(res.is_err());
+        run(&mut deps, "hosty", create("Red Clan")).unwrap();
+
+        // invite - fail because blue has no room left
+        for i in 1..MAX_GUILD_MEMBERS {
+            let player = format!("member_{}", i);
+            let invite = ExecuteMsg::InviteToGuild {
+                player: Addr::unchecked(&player),
+            };
+            run(&mut deps, "toasty", invite).unwrap();
+            run(
+                &mut deps,
+                &player,
+                ExecuteMsg::AcceptGuildInvite { guild_id: 2 },
+            )
+            .unwrap();
+        }
+        let invite = ExecuteMsg::InviteToGuild {
+            player: Addr::unchecked("ghosty"),
+        };
+        let res = run(&mut deps, "toasty", invite);
+        match res {
+            Err(ContractError::GuildFull {}) => {}
+            _ => panic!("GuildFull error should occur"),
+        }
+    }
+
+    #[test]
+    fn side_bets() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateSideBetFee { fee_bps: 10_001 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        match res {
+            Err(ContractError::InvalidSideBetFee {}) => {}
+            _ => panic!("InvalidSideBetFee error should occur"),
+        }
+        let msg = ExecuteMsg::UpdateSideBetFee { fee_bps: 500 };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let queue = |deps: &mut OwnedDeps<_, _, _>, player: &str, player_move| {
+            let msg = ExecuteMsg::JoinQueue {
+                wager: coin(10, "token"),
+                move_commitment: commit_move(player_move, &format!("{}_secret_move_salt", player)),
+                player: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(player, &coins(10, "token")),
+                msg,
+            )
+            .unwrap();
+        };
+        let bet = |deps: &mut OwnedDeps<_, _, _>, bettor: &str, outcome, stake: Coin| {
+            let msg = ExecuteMsg::PlaceSideBet {
+                game_id: 1,
+                outcome,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(bettor, &[stake]), msg)
+        };
+        let claim = |deps: &mut OwnedDeps<_, _, _>, bettor: &str, game_id| {
+            let msg = ExecuteMsg::ClaimSideBet { game_id };
+            execute(deps.as_mut(), mock_env(), mock_info(bettor, &[]), msg)
+        };
+        let reveal = |deps: &mut OwnedDeps<_, _, _>, player: &str, player_move| {
+            let msg = ExecuteMsg::RevealMove {
+                game_id: 1,
+                player_move,
+                salt: format!("{}_secret_move_salt", player),
+                player: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(player, &[]), msg).unwrap();
+        };
+
+        // place side bet - fail because round 1 is not a queued game
+        let msg = ExecuteMsg::CreateRound {
+            players: vec![Addr::unchecked("hosty"), Addr::unchecked("toasty")],
+            stake: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+
+        let res = bet(&mut deps, "alice", GameResult::HostWins, coin(5, "token"));
+        match res {
+            Err(ContractError::NoGameFound {}) => {}
+            _ => panic!("NoGameFound error should occur"),
+        }
+
+        queue(&mut deps, "hosty", GameMove::Rock);
+        queue(&mut deps, "toasty", GameMove::Paper);
+
+        // the players cannot bet on their own game
+        let res = bet(&mut deps, "hosty", GameResult::HostWins, coin(5, "token"));
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Unauthorized error should occur"),
+        }
+
+        bet(
+            &mut deps,
+            "alice",
+            GameResult::OpponentWins,
+            coin(60, "token"),
+        )
+        .unwrap();
+        bet(&mut deps, "bob", GameResult::HostWins, coin(40, "token")).unwrap();
+        bet(
+            &mut deps,
+            "carol",
+            GameResult::OpponentWins,
+            coin(20, "token"),
+        )
+        .unwrap();
+
+        // the first bet fixed the pool denom
+        let res = bet(&mut deps, "dave", GameResult::Tie, coin(20, "gold"));
+        match res {
+            Err(ContractError::InvalidSideBet {}) => {}
+            _ => panic!("InvalidSideBet error should occur"),
+        }
+        let res = claim(&mut deps, "alice", 1);
+        match res {
+            Err(ContractError::SideBetsNotSettled {}) => {}
+            _ => panic!("SideBetsNotSettled error should occur"),
+        }
+
+        // the first reveal closes the pool
+        reveal(&mut deps, "hosty", GameMove::Rock);
+        let res = bet(
+            &mut deps,
+            "dave",
+            GameResult::OpponentWins,
+            coin(20, "token"),
+        );
+        match res {
+            Err(ContractError::SideBetsClosed {}) => {}
+            _ => panic!("SideBetsClosed error should occur"),
+        }
+        reveal(&mut deps, "toasty", GameMove::Paper);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetSidePool { game_id: 1 },
+        )
+        .unwrap();
+        let pool: SidePool = from_binary(&res).unwrap();
+        assert_eq!(
+            SidePoolStatus::Settled {
+                result: GameResult::OpponentWins,
+                fee: Uint128::new(6),
+                claimed: Uint128::zero(),
+                paid: Uint128::zero(),
+            },
+            pool.status
+        );
+
+        // 114 after the fee is shared 60 to 20 between alice and carol
+        let res = claim(&mut deps, "alice", 1).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(85, "token"),
+            })
+        );
+        let res = claim(&mut deps, "carol", 1).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "carol".to_string(),
+                amount: coins(28, "token"),
+            })
+        );
+        let res = claim(&mut deps, "bob", 1).unwrap();
+        assert!(res.messages.is_empty());
+        let res = claim(&mut deps, "alice", 1);
+        match res {
+            Err(ContractError::NoSideBet {}) => {}
+            _ => panic!("NoSideBet error should occur"),
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetTreasury {}).unwrap();
+        let value: Vec<Coin> = from_binary(&res).unwrap();
+        // the fee and the token left over from rounding the shares
+        assert_eq!(coins(7, "token"), value);
+
+        // toasty never reveals the second game, the forfeit voids its pool
+        queue(&mut deps, "hosty", GameMove::Rock);
+        queue(&mut deps, "toasty", GameMove::Paper);
+        let msg = ExecuteMsg::PlaceSideBet {
+            game_id: 2,
+            outcome: GameResult::HostWins,
+        };
+        let info = mock_info("alice", &coins(30, "token"));
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::RevealMove {
+            game_id: 2,
+            player_move: GameMove::Rock,
+            salt: "hosty_secret_move_salt".to_string(),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2 * 60 * 60);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 2 };
+        let _res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+
+        let res = claim(&mut deps, "alice", 2).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(30, "token"),
+            })
+        );
     }
 
     #[test]
-    fn get_games_by_host() {
+    fn sponsored_games() {
         let mut deps = mock_dependencies();
 
         let msg = InstantiateMsg {
             admin: Addr::unchecked("creator"),
         };
-        let info = mock_info("creator", &coins(1000, "earth"));
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        //confirm instantiation response
-        assert_eq!("owner", res.attributes[1].key);
-        assert_eq!("creator", res.attributes[1].value);
+        let start = |deps: &mut OwnedDeps<_, _, _>| {
+            let msg = ExecuteMsg::StartGame {
+                opponent: Addr::unchecked("toasty"),
+                host_move: GameMove::Rock,
+                callback: None,
+                host: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+        };
+        let direct = || SponsoredGame::Direct {
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("toasty"),
+        };
+        let sponsor = |deps: &mut OwnedDeps<_, _, _>, sponsor: &str, game, bonus: &[Coin]| {
+            let msg = ExecuteMsg::SponsorGame { game };
+            execute(deps.as_mut(), mock_env(), mock_info(sponsor, bonus), msg)
+        };
+        let send = |to: &str, amount: Vec<Coin>| {
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: to.to_string(),
+                amount,
+            })
+        };
 
-        // execute start game 1
-        let auth_info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
-            host_move: GameMove::Rock,
+        let res = sponsor(&mut deps, "acme", direct(), &coins(10, "token"));
+        match res {
+            Err(ContractError::NoGameFound {}) => {}
+            _ => panic!("NoGameFound error should occur"),
+        }
+
+        start(&mut deps);
+        let res = sponsor(&mut deps, "acme", direct(), &[]);
+        match res {
+            Err(ContractError::InvalidSponsorBonus {}) => {}
+            _ => panic!("InvalidSponsorBonus error should occur"),
+        }
+
+        let res = sponsor(&mut deps, "acme", direct(), &coins(10, "token")).unwrap();
+        assert_eq!(
+            Ok(GameSponsoredEvent {
+                kind: GameKind::Direct,
+                game_id: None,
+                host: Addr::unchecked("hosty"),
+                opponent: Some(Addr::unchecked("toasty")),
+                sponsor: Addr::unchecked("acme"),
+                bonus: coin(10, "token"),
+            }),
+            GameSponsoredEvent::parse(&res.events[0])
+        );
+        sponsor(&mut deps, "dao", direct(), &coins(5, "gold")).unwrap();
+
+        let msg = QueryMsg::GetGameByHostAndOpponent {
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("toasty"),
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let game: Game = from_binary(&res).unwrap();
+        assert_eq!(2, game.sponsors.len());
 
-        // execute start game 2
-        let auth_info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player_2"),
-            host_move: GameMove::Rock,
+        // a tie splits every bonus, the host gets the remainder
+        let msg = ExecuteMsg::OpponentResponse {
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("toasty"),
+            opp_move: GameMove::Rock,
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("toasty", &[]), msg).unwrap();
+        assert_eq!(
+            vec![
+                send("hosty", vec![coin(3, "gold"), coin(5, "token")]),
+                send("toasty", vec![coin(2, "gold"), coin(5, "token")]),
+            ],
+            res.messages
+                .iter()
+                .map(|m| m.msg.clone())
+                .collect::<Vec<_>>()
+        );
 
-        // execute start game 3 - other creator
-        let auth_info = mock_info("other_creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGameRecord { game_id: 1 },
+        )
+        .unwrap();
+        let record: GameRecord = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("dao"), record.sponsors[1].sponsor);
+
+        // a sponsor topping up keeps a single slot, the others fill the rest
+        start(&mut deps);
+        sponsor(&mut deps, "acme", direct(), &coins(10, "token")).unwrap();
+        sponsor(&mut deps, "acme", direct(), &coins(5, "token")).unwrap();
+        for i in 1..MAX_SPONSORS {
+            let name = format!("sponsor_{}", i);
+            sponsor(&mut deps, &name, direct(), &coins(1, "token")).unwrap();
+        }
+
+        // sponsor - fail because the game has no slot left
+        let res = sponsor(&mut deps, "latecomer", direct(), &coins(1, "token"));
+        match res {
+            Err(ContractError::TooManySponsors {}) => {}
+            _ => panic!("TooManySponsors error should occur"),
+        }
+
+        // a cancelled game refunds its sponsors
+        let msg = ExecuteMsg::CancelGame {
+            opponent: Addr::unchecked("toasty"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
+        assert_eq!(MAX_SPONSORS, res.messages.len());
+        assert_eq!(send("acme", coins(15, "token")), res.messages[0].msg);
+
+        // the winner of a round takes the bonus
+        let msg = ExecuteMsg::CreateRound {
+            players: vec![Addr::unchecked("alice"), Addr::unchecked("bob")],
+            stake: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        let round = SponsoredGame::Round { round_id: 1 };
+        sponsor(&mut deps, "acme", round, &coins(20, "token")).unwrap();
+
+        let commit = |deps: &mut OwnedDeps<_, _, _>, player: &str, player_move| {
+            let msg = ExecuteMsg::CommitRoundMove {
+                round_id: 1,
+                move_commitment: commit_move(player_move, &format!("{}_secret_move_salt", player)),
+                player: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(player, &[]), msg).unwrap();
+        };
+        commit(&mut deps, "alice", GameMove::Paper);
+        commit(&mut deps, "bob", GameMove::Rock);
+
+        let msg = ExecuteMsg::RevealRoundMove {
+            round_id: 1,
+            player_move: GameMove::Paper,
+            salt: "alice_secret_move_salt".to_string(),
+            player: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        let msg = ExecuteMsg::RevealRoundMove {
+            round_id: 1,
+            player_move: GameMove::Rock,
+            salt: "bob_secret_move_salt".to_string(),
+            player: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg).unwrap();
+        assert_eq!(send("alice", coins(20, "token")), res.messages[0].msg);
+
+        let res = sponsor(
+            &mut deps,
+            "acme",
+            SponsoredGame::Round { round_id: 1 },
+            &coins(1, "token"),
+        );
+        match res {
+            Err(ContractError::WrongRoundPhase { .. }) => {}
+            _ => panic!("WrongRoundPhase error should occur"),
+        }
+    }
+
+    #[test]
+    fn operators() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let start_for_hosty = || ExecuteMsg::StartGame {
+            opponent: Addr::unchecked("toasty"),
             host_move: GameMove::Rock,
+            callback: None,
+            host: Some(Addr::unchecked("hosty")),
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
 
-        //query game by non-host = zero games found
+        // start game for hosty - fail because hotkey has no grant
+        let auth_info = mock_info("hotkey", &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            auth_info.clone(),
+            start_for_hosty(),
+        );
+
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Unauthorized error should occur"),
+        }
+
+        // grant operators - hosty to hotkey, toasty to toastkey
+        let env = mock_env();
+        let msg = ExecuteMsg::GrantOperator {
+            operator: Addr::unchecked("hotkey"),
+            expires: Expiration::AtHeight(env.block.height + 100),
+            max_wager: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("hosty", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::GrantOperator {
+            operator: Addr::unchecked("toastkey"),
+            expires: Expiration::AtHeight(env.block.height + 100),
+            max_wager: Some(coin(10, "token")),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("toasty", &[]), msg).unwrap();
+
         let res = query(
             deps.as_ref(),
             mock_env(),
-            QueryMsg::GetGamesByHost {
-                host: Addr::unchecked("non_host"),
+            QueryMsg::ListOperators {
+                player: Addr::unchecked("toasty"),
             },
         )
         .unwrap();
-        let value: GamesListResponse = from_binary(&res).unwrap();
-        assert_eq!(0, value.games.len());
+        let value: OperatorsResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.operators.len());
+        assert_eq!(Addr::unchecked("toastkey"), value.operators[0].operator);
+        assert_eq!(Some(coin(10, "token")), value.operators[0].max_wager);
+
+        // start game for hosty - the game belongs to hosty
+        let res = execute(deps.as_mut(), mock_env(), auth_info, start_for_hosty()).unwrap();
+        assert_eq!("hosty", res.attributes[1].value);
 
-        //query game by host = 2 games found
         let res = query(
             deps.as_ref(),
             mock_env(),
-            QueryMsg::GetGamesByHost {
-                host: Addr::unchecked("creator"),
+            QueryMsg::GetGameByHostAndOpponent {
+                host: Addr::unchecked("hosty"),
+                opponent: Addr::unchecked("toasty"),
             },
-        )
-        .unwrap();
+        );
+        assert!(res.is_ok());
 
-        let value: GamesListResponse = from_binary(&res).unwrap();
-        assert_eq!(2, value.games.len());
-        assert_eq!(Addr::unchecked("creator"), value.games[0].host);
-        assert_eq!(Addr::unchecked("other_player"), value.games[0].opponent);
-        assert_eq!(GameMove::Rock, value.games[0].host_move);
-        assert_eq!(None, value.games[0].opp_move);
-        assert_eq!(None, value.games[0].result);
-    }
+        // toastkey answers for toasty
+        let msg = ExecuteMsg::OpponentResponse {
+            host: Addr::unchecked("hosty"),
+            opponent: Addr::unchecked("toasty"),
+            opp_move: GameMove::Paper,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("toastkey", &[]), msg).unwrap();
+        assert_eq!("Opponent Wins", res.attributes[3].value);
 
-    #[test]
-    fn get_games_by_opp() {
-        let mut deps = mock_dependencies();
+        let queue_for = |player: &str, amount: u128| ExecuteMsg::JoinQueue {
+            wager: coin(amount, "token"),
+            move_commitment: commit_move(GameMove::Rock, "operator_secret_salt"),
+            player: Some(Addr::unchecked(player)),
+        };
 
-        let msg = InstantiateMsg {
-            admin: Addr::unchecked("creator"),
+        // join queue for hosty - fail because hotkey's grant allows no wager
+        let info = mock_info("hotkey", &coins(5, "token"));
+        let res = execute(deps.as_mut(), mock_env(), info, queue_for("hosty", 5));
+
+        match res {
+            Err(ContractError::InvalidWager {}) => {}
+            _ => panic!("InvalidWager error should occur"),
+        }
+
+        // join queue for toasty - fail because 20 token is over toastkey's max_wager
+        let info = mock_info("toastkey", &coins(20, "token"));
+        let res = execute(deps.as_mut(), mock_env(), info, queue_for("toasty", 20));
+
+        match res {
+            Err(ContractError::InvalidWager {}) => {}
+            _ => panic!("InvalidWager error should occur"),
+        }
+
+        // toastkey queues 10 token for toasty, the entry and its refund are toasty's
+        let info = mock_info("toastkey", &coins(10, "token"));
+        let _res = execute(deps.as_mut(), mock_env(), info, queue_for("toasty", 10)).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetQueue {}).unwrap();
+        let value: QueueResponse = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("toasty"), value.entries[0].player);
+
+        let msg = ExecuteMsg::LeaveQueue {
+            player: Some(Addr::unchecked("toasty")),
         };
-        let info = mock_info("creator", &coins(1000, "earth"));
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("toastkey", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "toasty".to_string(),
+                amount: coins(10, "token"),
+            })
+        );
 
-        //confirm instantiation response
-        assert_eq!("owner", res.attributes[1].key);
-        assert_eq!("creator", res.attributes[1].value);
+        // revoked grants no longer work
+        let msg = ExecuteMsg::RevokeOperator {
+            operator: Addr::unchecked("hotkey"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg).unwrap();
 
-        // execute start game 1
-        let auth_info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
-            host_move: GameMove::Rock,
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hotkey", &[]),
+            start_for_hosty(),
+        );
+
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Unauthorized error should occur"),
+        }
+
+        let msg = ExecuteMsg::RevokeOperator {
+            operator: Addr::unchecked("hotkey"),
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg);
 
-        // execute start game 2
-        let auth_info = mock_info("creator", &coins(2, "token"));
+        match res {
+            Err(ContractError::OperatorNotFound {}) => {}
+            _ => panic!("OperatorNotFound error should occur"),
+        }
+
+        // expired grants no longer work
+        let mut env = mock_env();
+        env.block.height += 100;
         let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player_2"),
+            opponent: Addr::unchecked("hosty"),
             host_move: GameMove::Rock,
+            callback: None,
+            host: Some(Addr::unchecked("toasty")),
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        let res = execute(deps.as_mut(), env, mock_info("toastkey", &[]), msg);
 
-        // execute start game 3 - other creator
-        let auth_info = mock_info("other_creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
-            host_move: GameMove::Rock,
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Unauthorized error should occur"),
+        }
+    }
+
+    // answers smart queries to "botty" with paper, checking it is asked about hosty's game
+    struct BotQuerier(MockQuerier);
+
+    impl Querier for BotQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+            match request {
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                    if contract_addr == "botty" =>
+                {
+                    let BotQueryMsg::GetMove {
+                        game_id,
+                        host,
+                        nonce,
+                    } = from_binary(&msg).unwrap();
+                    assert!(game_id > 0);
+                    assert_eq!(Addr::unchecked("hosty"), host);
+                    assert_eq!(32, nonce.len());
+
+                    let answer = BotMoveResponse {
+                        bot_move: GameMove::Paper,
+                    };
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&answer).unwrap()))
+                }
+                _ => self.0.raw_query(bin_request),
+            }
+        }
+    }
+
+    #[test]
+    fn bot_games() {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: BotQuerier(MockQuerier::default()),
+            custom_query_type: PhantomData,
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
 
-        // execute start game 4 - another creator
-        let auth_info = mock_info("another_creator", &coins(2, "token"));
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let play_bot_game = |host_move| ExecuteMsg::PlayBotGame {
+            bot: Addr::unchecked("botty"),
+            host_move,
+        };
+
+        // play bot game - fail because botty has not opted in
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hosty", &[]),
+            play_bot_game(GameMove::Rock),
+        );
+
+        match res {
+            Err(ContractError::BotNotRegistered {}) => {}
+            _ => panic!("BotNotRegistered error should occur"),
+        }
+
+        // botty opts in
+        let msg = ExecuteMsg::RegisterBot {};
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("botty", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ListBots {}).unwrap();
+        let value: BotsResponse = from_binary(&res).unwrap();
+        assert_eq!(vec![Addr::unchecked("botty")], value.bots);
+
+        // start game - fail because a bot could read the plain move
         let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
+            opponent: Addr::unchecked("botty"),
             host_move: GameMove::Rock,
+            callback: None,
+            host: None,
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("hosty", &[]), msg);
 
-        //query game by non-opponent = zero games found
-        let res = query(
-            deps.as_ref(),
+        match res {
+            Err(ContractError::BotGameRequired {}) => {}
+            _ => panic!("BotGameRequired error should occur"),
+        }
+
+        // botty answers paper in the same transaction and hosty's rock loses
+        let res = execute(
+            deps.as_mut(),
             mock_env(),
-            QueryMsg::GetGamesByOpponent {
-                opponent: Addr::unchecked("non_opponent"),
-            },
+            mock_info("hosty", &[]),
+            play_bot_game(GameMove::Rock),
         )
         .unwrap();
-        let value: GamesListResponse = from_binary(&res).unwrap();
-        assert_eq!(0, value.games.len());
+        assert_eq!("try_play_bot_game", res.attributes[0].value);
+        assert_eq!("Paper", res.attributes[4].value);
+        assert_eq!("Opponent Wins", res.attributes[5].value);
+        let resolved = GameResolvedEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(GameKind::Bot, resolved.kind);
+        assert_eq!(Some(1), resolved.game_id);
+        assert_eq!(Some(GameMove::Paper), resolved.opp_move);
 
-        //query game by opponent = 3 games found
+        // the record is flagged and never ranked, botty's owner could throw its games
         let res = query(
             deps.as_ref(),
             mock_env(),
-            QueryMsg::GetGamesByOpponent {
-                opponent: Addr::unchecked("other_player"),
-            },
+            QueryMsg::GetGameRecord { game_id: 1 },
         )
         .unwrap();
+        let value: GameRecord = from_binary(&res).unwrap();
+        assert!(value.bot);
+        assert!(!value.ranked);
 
-        let value: GamesListResponse = from_binary(&res).unwrap();
-        assert_eq!(3, value.games.len());
-        assert_eq!(Addr::unchecked("creator"), value.games[0].host);
-        assert_eq!(Addr::unchecked("other_player"), value.games[0].opponent);
-        assert_eq!(GameMove::Rock, value.games[0].host_move);
-        assert_eq!(None, value.games[0].opp_move);
-        assert_eq!(None, value.games[0].result);
+        // scissors beats paper
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hosty", &[]),
+            play_bot_game(GameMove::Scissors),
+        )
+        .unwrap();
+        let resolved = GameResolvedEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(Some(2), resolved.game_id);
+        assert_eq!(GameResult::HostWins, resolved.result);
+
+        // play bot game - fail because botty opted out again
+        let msg = ExecuteMsg::UnregisterBot {};
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("botty", &[]), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("hosty", &[]),
+            play_bot_game(GameMove::Rock),
+        );
+
+        match res {
+            Err(ContractError::BotNotRegistered {}) => {}
+            _ => panic!("BotNotRegistered error should occur"),
+        }
     }
 
     #[test]
-    fn get_game_by_host_and_opp() {
-        let mut deps = mock_dependencies();
+    fn relayed_moves() {
+        let mut deps = relay_dependencies();
+        let player = relay_testing::player(PLAYER_KEY);
 
         let msg = InstantiateMsg {
             admin: Addr::unchecked("creator"),
         };
-        let info = mock_info("creator", &coins(1000, "earth"));
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-        //confirm instantiation response
-        assert_eq!("owner", res.attributes[1].key);
-        assert_eq!("creator", res.attributes[1].value);
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // execute start game 1
-        let auth_info = mock_info("creator", &coins(2, "token"));
+        let auth_info = mock_info("hosty", &[]);
         let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
+            opponent: player.clone(),
             host_move: GameMove::Rock,
+            callback: None,
+            host: None,
         };
         let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
 
-        // execute start game 2
-        let auth_info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player_2"),
-            host_move: GameMove::Rock,
+        let relayed = |payload: &RelayedPayload| {
+            let signed_payload = to_vec(payload).unwrap();
+            ExecuteMsg::RelayedMove {
+                signature: relay_testing::sign(PLAYER_KEY, &signed_payload).into(),
+                signed_payload: signed_payload.into(),
+                pubkey: relay_testing::pubkey(PLAYER_KEY).into(),
+            }
+        };
+        let mut payload = RelayedPayload {
+            chain_id: mock_env().block.chain_id,
+            contract: mock_env().contract.address,
+            player: player.clone(),
+            nonce: 0,
+            action: RelayedAction::OpponentResponse {
+                host: Addr::unchecked("hosty"),
+                opp_move: GameMove::Paper,
+            },
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
 
-        // execute start game 3 - other creator
-        let auth_info = mock_info("other_creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
-            host_move: GameMove::Rock,
+        // relayed move - fail because the payload is for another chain
+        let relayer = mock_info("relayer", &[]);
+        let msg = relayed(&RelayedPayload {
+            chain_id: "other-chain".to_string(),
+            ..payload.clone()
+        });
+        let res = execute(deps.as_mut(), mock_env(), relayer.clone(), msg);
+
+        match res {
+            Err(ContractError::WrongChainId {}) => {}
+            _ => panic!("WrongChainId error should occur"),
+        }
+
+        // relayed move - fail because the payload is for another deployment
+        let msg = relayed(&RelayedPayload {
+            contract: Addr::unchecked("other_rps"),
+            ..payload.clone()
+        });
+        let res = execute(deps.as_mut(), mock_env(), relayer.clone(), msg);
+
+        match res {
+            Err(ContractError::WrongContract {}) => {}
+            _ => panic!("WrongContract error should occur"),
+        }
+
+        // relayed move - fail because the key does not belong to the player
+        let msg = relayed(&RelayedPayload {
+            player: Addr::unchecked("hosty"),
+            ..payload.clone()
+        });
+        let res = execute(deps.as_mut(), mock_env(), relayer.clone(), msg);
+
+        match res {
+            Err(ContractError::PubkeyMismatch {}) => {}
+            _ => panic!("PubkeyMismatch error should occur"),
+        }
+
+        // relayed move - fail because the signature does not cover the payload
+        let msg = match relayed(&payload) {
+            ExecuteMsg::RelayedMove {
+                pubkey, signature, ..
+            } => ExecuteMsg::RelayedMove {
+                signed_payload: to_binary(&RelayedPayload {
+                    nonce: 1,
+                    ..payload.clone()
+                })
+                .unwrap(),
+                pubkey,
+                signature,
+            },
+            _ => unreachable!(),
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), relayer.clone(), msg);
 
-        //query game non-players = zero games found
-        let res = query(
-            deps.as_ref(),
+        match res {
+            Err(ContractError::InvalidSignature {}) => {}
+            _ => panic!("InvalidSignature error should occur"),
+        }
+
+        // relayed move - the game is played as the player, the relayer only pays gas
+        let res = execute(
+            deps.as_mut(),
             mock_env(),
-            QueryMsg::GetGameByHostAndOpponent {
-                host: Addr::unchecked("non_host"),
-                opponent: Addr::unchecked("non_opponent"),
-            },
+            relayer.clone(),
+            relayed(&payload),
+        )
+        .unwrap();
+
+        assert_eq!("result", res.attributes[3].key);
+        assert_eq!("Opponent Wins", res.attributes[3].value);
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "relayer" && attr.value == "relayer"));
+
+        // replaying the same payload - fail because the nonce was used
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            relayer.clone(),
+            relayed(&payload),
         );
 
         match res {
-            Err(_) => {}
-            _ => panic!("Should error out."),
+            Err(ContractError::InvalidNonce { expected: 1 }) => {}
+            _ => panic!("InvalidNonce error should occur"),
         }
 
-        //query game by host and opponent = 1 game found
         let res = query(
             deps.as_ref(),
             mock_env(),
-            QueryMsg::GetGameByHostAndOpponent {
-                host: Addr::unchecked("creator"),
-                opponent: Addr::unchecked("other_player"),
+            QueryMsg::GetRelayNonce {
+                player: player.clone(),
             },
         )
         .unwrap();
+        let value: RelayNonceResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.nonce);
 
-        let value: Game = from_binary(&res).unwrap();
-        assert_eq!(Addr::unchecked("creator"), value.host);
-        assert_eq!(Addr::unchecked("other_player"), value.opponent);
-        assert_eq!(GameMove::Rock, value.host_move);
-        assert_eq!(None, value.opp_move);
-        assert_eq!(None, value.result);
+        // the next nonce can start a new game for the player
+        payload.nonce = 1;
+        payload.action = RelayedAction::StartGame {
+            opponent: Addr::unchecked("hosty"),
+            host_move: GameMove::Scissors,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), relayer, relayed(&payload)).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByHost { host: player },
+        )
+        .unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.games.len());
     }
 
     #[test]
-    fn update_admin() {
-        let mut deps = mock_dependencies();
+    fn state_channels() {
+        let mut deps = relay_dependencies();
+        let player_a = relay_testing::player(PLAYER_KEY);
+        let player_b = relay_testing::player(OTHER_KEY);
 
         let msg = InstantiateMsg {
             admin: Addr::unchecked("creator"),
         };
-        let info = mock_info("creator", &coins(1000, "earth"));
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        //confirm instantiation response
-        assert_eq!("owner", res.attributes[1].key);
-        assert_eq!("creator", res.attributes[1].value);
+        // open channel - fail because the key is not player a's
+        let info_a = mock_info(player_a.as_str(), &coins(10, "token"));
+        let msg = ExecuteMsg::OpenChannel {
+            counterparty: player_b.clone(),
+            pubkey: relay_testing::pubkey(OTHER_KEY).into(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info_a.clone(), msg);
 
-        // execute start game 1
-        let auth_info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
-            host_move: GameMove::Rock,
+        match res {
+            Err(ContractError::PubkeyMismatch {}) => {}
+            _ => panic!("PubkeyMismatch error should occur"),
+        }
+
+        // open and join channel 1 with 10 token each
+        let msg = ExecuteMsg::OpenChannel {
+            counterparty: player_b.clone(),
+            pubkey: relay_testing::pubkey(PLAYER_KEY).into(),
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        let _res = execute(deps.as_mut(), mock_env(), info_a.clone(), msg).unwrap();
 
-        //query first admin
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdmin {}).unwrap();
+        let info_b = mock_info(player_b.as_str(), &coins(10, "token"));
+        let msg = ExecuteMsg::JoinChannel {
+            channel_id: 1,
+            pubkey: relay_testing::pubkey(OTHER_KEY).into(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info_b.clone(), msg).unwrap();
 
-        let value: Addr = from_binary(&res).unwrap();
-        assert_eq!(Addr::unchecked("creator"), value);
+        let env = mock_env();
+        let tally = |channel_id: u64, nonce: u64, balance_a: u128, is_final: bool| {
+            let payload = to_vec(&ChannelState {
+                chain_id: env.block.chain_id.clone(),
+                contract: env.contract.address.clone(),
+                channel_id,
+                nonce,
+                balance_a: Uint128::new(balance_a),
+                balance_b: Uint128::new(20 - balance_a),
+                is_final,
+            })
+            .unwrap();
+            SignedChannelState {
+                signature_a: relay_testing::sign(PLAYER_KEY, &payload).into(),
+                signature_b: relay_testing::sign(OTHER_KEY, &payload).into(),
+                payload: payload.into(),
+            }
+        };
+        let signed_move = |key: u8, nonce: u64, player_move: GameMove| {
+            let payload = to_vec(&ChannelMove {
+                chain_id: env.block.chain_id.clone(),
+                contract: env.contract.address.clone(),
+                channel_id: 1,
+                nonce,
+                move_commitment: commit_move(
+                    player_move,
+                    &format!("channel_secret_salt_{}", nonce),
+                ),
+                stake: Uint128::new(5),
+            })
+            .unwrap();
+            SignedChannelMove {
+                signature: relay_testing::sign(key, &payload).into(),
+                payload: payload.into(),
+            }
+        };
 
-        // update admin - fail
-        let auth_info = mock_info("not_admin", &coins(2, "token"));
-        let msg = ExecuteMsg::UpdateAdmin {
-            admin: Addr::unchecked("other_admin"),
+        // player a settles the tally after 3 rounds, which starts the challenge period
+        let msg = ExecuteMsg::SettleChannel {
+            channel_id: 1,
+            state: tally(1, 3, 14, false),
         };
-        let res = execute(deps.as_mut(), mock_env(), auth_info, msg);
+        let _res = execute(deps.as_mut(), mock_env(), info_a.clone(), msg).unwrap();
+
+        // settle an older tally - fail because a later one is settled
+        let msg = ExecuteMsg::SettleChannel {
+            channel_id: 1,
+            state: tally(1, 2, 16, false),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info_b.clone(), msg);
 
         match res {
-            Err(_) => {}
-            _ => panic!("This is supposed to return a Contract Error"),
+            Err(ContractError::StaleChannelState {}) => {}
+            _ => panic!("StaleChannelState error should occur"),
+        }
+
+        let reveal = |player_move: GameMove, nonce: u64| ExecuteMsg::RevealChannelMove {
+            channel_id: 1,
+            player_move,
+            salt: format!("channel_secret_salt_{}", nonce),
         };
 
-        // update admin - success
-        let auth_info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::UpdateAdmin {
-            admin: Addr::unchecked("other_admin"),
+        // player b disputes the 4th round, which a lost but did not sign off
+        let msg = ExecuteMsg::DisputeChannelRound {
+            channel_id: 1,
+            move_a: signed_move(PLAYER_KEY, 4, GameMove::Rock),
+            move_b: signed_move(OTHER_KEY, 4, GameMove::Paper),
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        let _res = execute(deps.as_mut(), mock_env(), info_b.clone(), msg).unwrap();
 
-        //query updated admin
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdmin {}).unwrap();
+        // reveal - fail because a reveals another move than committed
+        let msg = reveal(GameMove::Scissors, 4);
+        let res = execute(deps.as_mut(), mock_env(), info_a.clone(), msg);
 
-        let value: Addr = from_binary(&res).unwrap();
-        assert_eq!(Addr::unchecked("other_admin"), value);
-    }
+        match res {
+            Err(ContractError::CommitmentMismatch {}) => {}
+            _ => panic!("CommitmentMismatch error should occur"),
+        }
 
-    #[test]
-    fn blacklist() {
-        let mut deps = mock_dependencies();
+        // the round is decided once both moves are revealed
+        let msg = reveal(GameMove::Paper, 4);
+        let _res = execute(deps.as_mut(), mock_env(), info_b.clone(), msg).unwrap();
+        let msg = reveal(GameMove::Rock, 4);
+        let res = execute(deps.as_mut(), mock_env(), info_a.clone(), msg).unwrap();
+        assert_eq!("Opponent Wins", res.attributes[3].value);
 
-        let msg = InstantiateMsg {
-            admin: Addr::unchecked("creator"),
+        // moves signed by the wrong player are rejected
+        let msg = ExecuteMsg::DisputeChannelRound {
+            channel_id: 1,
+            move_a: signed_move(OTHER_KEY, 5, GameMove::Rock),
+            move_b: signed_move(OTHER_KEY, 5, GameMove::Paper),
         };
-        let info = mock_info("creator", &coins(1000, "earth"));
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info_b.clone(), msg);
 
-        //confirm instantiation response
-        assert_eq!("owner", res.attributes[1].key);
-        assert_eq!("creator", res.attributes[1].value);
+        match res {
+            Err(ContractError::InvalidSignature {}) => {}
+            _ => panic!("InvalidSignature error should occur"),
+        }
 
-        // add address to blacklist
-        let auth_info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::AddToBlacklist {
-            address: Addr::unchecked("bad_guy"),
+        // b disputes the 5th round half way through the challenge period, a never reveals
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(30 * 60);
+        let msg = ExecuteMsg::DisputeChannelRound {
+            channel_id: 1,
+            move_a: signed_move(PLAYER_KEY, 5, GameMove::Scissors),
+            move_b: signed_move(OTHER_KEY, 5, GameMove::Rock),
         };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info_b.clone(), msg.clone()).unwrap();
 
-        // execute start game - fail because host is blacklisted
-        let auth_info = mock_info("bad_guy", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
-            host_move: GameMove::Rock,
-        };
-        let res = execute(deps.as_mut(), mock_env(), auth_info, msg);
+        let res = execute(deps.as_mut(), env.clone(), info_b.clone(), msg);
 
         match res {
-            Err(ContractError::OnTheBlacklist {}) => {}
-            _ => panic!("OnTheBlacklist error should occur"),
-        };
+            Err(ContractError::ChannelRoundDisputed {}) => {}
+            _ => panic!("ChannelRoundDisputed error should occur"),
+        }
 
-        // remove address from blacklist
-        let auth_info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::RemoveFromBlacklist {
-            address: Addr::unchecked("bad_guy"),
-        };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        let _res = execute(deps.as_mut(), env, info_b, reveal(GameMove::Rock, 5)).unwrap();
 
-        // execute start game - success because host has been removed from blacklist
-        let auth_info = mock_info("bad_guy", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
-            host_move: GameMove::Rock,
-        };
-        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        // finalize - fail because the challenge period is running
+        let msg = ExecuteMsg::FinalizeChannel { channel_id: 1 };
+        let res = execute(deps.as_mut(), mock_env(), info_a.clone(), msg.clone());
 
-        //query game by host and opponent = 1 game found
-        let res = query(
-            deps.as_ref(),
-            mock_env(),
-            QueryMsg::GetGameByHostAndOpponent {
-                host: Addr::unchecked("bad_guy"),
-                opponent: Addr::unchecked("other_player"),
-            },
-        )
-        .unwrap();
+        match res {
+            Err(ContractError::ChallengePeriodNotOver {}) => {}
+            _ => panic!("ChallengePeriodNotOver error should occur"),
+        }
 
-        let value: Game = from_binary(&res).unwrap();
-        assert_eq!(Addr::unchecked("bad_guy"), value.host);
-        assert_eq!(Addr::unchecked("other_player"), value.opponent);
-        assert_eq!(GameMove::Rock, value.host_move);
-        assert_eq!(None, value.opp_move);
-        assert_eq!(None, value.result);
-    }
+        // finalize - fail because a can still reveal the 5th round
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60 * 60);
+        let res = execute(deps.as_mut(), env.clone(), info_a.clone(), msg.clone());
 
-    #[test]
-    fn full_game() {
-        let mut deps = mock_dependencies();
+        match res {
+            Err(ContractError::ChallengePeriodNotOver {}) => {}
+            _ => panic!("ChallengePeriodNotOver error should occur"),
+        }
 
-        let msg = InstantiateMsg {
-            admin: Addr::unchecked("creator"),
-        };
-        let info = mock_info("creator", &coins(1000, "earth"));
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        // reveal - fail because the dispute expired
+        env.block.time = env.block.time.plus_seconds(30 * 60);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info_a.clone(),
+            reveal(GameMove::Scissors, 5),
+        );
 
-        //confirm instantiation response
-        assert_eq!("owner", res.attributes[1].key);
-        assert_eq!("creator", res.attributes[1].value);
+        match res {
+            Err(ContractError::RevealExpired {}) => {}
+            _ => panic!("RevealExpired error should occur"),
+        }
 
-        // execute start game
-        let auth_info = mock_info("hosty", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("toasty"),
-            host_move: GameMove::Rock,
-        };
+        // finalize pays out the disputed tally, a forfeits the unrevealed 5th round
+        let res = execute(deps.as_mut(), env, info_a.clone(), msg).unwrap();
 
-        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
-        assert_eq!("host", res.attributes[1].key);
-        assert_eq!("hosty", res.attributes[1].value);
-        assert_eq!("opponent", res.attributes[2].key);
-        assert_eq!("toasty", res.attributes[2].value);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: player_a.to_string(),
+                amount: coins(4, "token"),
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: player_b.to_string(),
+                amount: coins(16, "token"),
+            })
+        );
+        let resolved = GameResolvedEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(GameKind::Channel, resolved.kind);
+        assert_eq!(GameResult::OpponentWins, resolved.result);
 
-        // execute opponent reponse
-        let auth_info = mock_info("toasty", &coins(2, "token"));
-        let msg = ExecuteMsg::OpponentResponse {
-            host: Addr::unchecked("hosty"),
-            opponent: Addr::unchecked("toasty"),
-            opp_move: GameMove::Rock,
+        // a co-signed final tally closes channel 2 right away
+        let msg = ExecuteMsg::OpenChannel {
+            counterparty: player_b.clone(),
+            pubkey: relay_testing::pubkey(PLAYER_KEY).into(),
         };
+        let _res = execute(deps.as_mut(), mock_env(), info_a.clone(), msg).unwrap();
 
-        let res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+        let msg = ExecuteMsg::JoinChannel {
+            channel_id: 2,
+            pubkey: relay_testing::pubkey(OTHER_KEY).into(),
+        };
+        let info_b = mock_info(player_b.as_str(), &coins(10, "token"));
+        let _res = execute(deps.as_mut(), mock_env(), info_b, msg).unwrap();
 
-        assert_eq!("result", res.attributes[3].key);
-        assert_eq!("Tie", res.attributes[3].value);
+        let msg = ExecuteMsg::SettleChannel {
+            channel_id: 2,
+            state: tally(2, 7, 20, true),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info_a, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+        let resolved = GameResolvedEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(Some(2), resolved.game_id);
+        assert_eq!(GameResult::HostWins, resolved.result);
 
-        //query for game - fail
         let res = query(
             deps.as_ref(),
             mock_env(),
-            QueryMsg::GetGameByHostAndOpponent {
-                host: Addr::unchecked("hosty"),
-                opponent: Addr::unchecked("toasty"),
-            },
-        );
-        //confirms game data was deleted
-        match res {
-            Err(_) => {}
-            _ => panic!("No Game Found Error should occur"),
-        }
+            QueryMsg::GetChannel { channel_id: 2 },
+        )
+        .unwrap();
+        let channel: Channel = from_binary(&res).unwrap();
+        assert_eq!(ChannelStatus::Closed, channel.status);
     }
 }
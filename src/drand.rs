@@ -0,0 +1,139 @@
+use std::convert::TryInto;
+
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{pairing, G1Affine, G2Affine, G2Projective};
+use sha2::{Digest, Sha256};
+
+use crate::state::GameMove;
+
+// domain separation tag used by the drand chained beacons (pubkey on G1, signature on G2)
+const DOMAIN: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+// drand signs sha256(previous_signature || round) for every round
+pub fn round_message(round: u64, previous_signature: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(previous_signature);
+    hasher.update(round.to_be_bytes());
+    hasher.finalize().into()
+}
+
+pub fn verify_beacon(
+    pubkey: &[u8],
+    round: u64,
+    previous_signature: &[u8],
+    signature: &[u8],
+) -> bool {
+    let pubkey: Option<G1Affine> = match pubkey.try_into() {
+        Ok(bytes) => G1Affine::from_compressed(bytes).into(),
+        Err(_) => None,
+    };
+    let signature: Option<G2Affine> = match signature.try_into() {
+        Ok(bytes) => G2Affine::from_compressed(bytes).into(),
+        Err(_) => None,
+    };
+
+    match (pubkey, signature) {
+        (Some(pubkey), Some(signature)) => {
+            let msg = round_message(round, previous_signature);
+            let msg_on_curve =
+                <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(msg, DOMAIN);
+
+            pairing(&G1Affine::generator(), &signature)
+                == pairing(&pubkey, &G2Affine::from(msg_on_curve))
+        }
+        _ => false,
+    }
+}
+
+// the beacon randomness is sha256(signature), the house move is taken from its first 8 bytes
+pub fn derive_move(signature: &[u8]) -> GameMove {
+    let randomness: [u8; 32] = Sha256::digest(signature).into();
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&randomness[..8]);
+
+    match u64::from_be_bytes(head) % 3 {
+        0 => GameMove::Rock,
+        1 => GameMove::Paper,
+        _ => GameMove::Scissors,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+    use bls12_381::Scalar;
+
+    // a fixed key standing in for the drand network when signing test beacons
+    const SECRET_KEY: u64 = 0x0064_7261_6e64;
+
+    pub fn pubkey() -> Vec<u8> {
+        let pk = G1Affine::from(G1Affine::generator() * Scalar::from(SECRET_KEY));
+        pk.to_compressed().to_vec()
+    }
+
+    pub fn sign(round: u64, previous_signature: &[u8]) -> Vec<u8> {
+        let msg = round_message(round, previous_signature);
+        let msg_on_curve =
+            <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(msg, DOMAIN);
+        G2Affine::from(msg_on_curve * Scalar::from(SECRET_KEY))
+            .to_compressed()
+            .to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::{pubkey, sign};
+    use super::*;
+
+    // a beacon recorded from the drand mainnet chain, checks the domain tag,
+    // the message format and hash to curve against real drand output
+    const MAINNET_PUBKEY: &str = "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31";
+    const MAINNET_ROUND: u64 = 72785;
+    const MAINNET_PREVIOUS_SIGNATURE: &str = "a609e19a03c2fcc559e8dae14900aaefe517cb55c840f6e69bc8e4f66c8d18e8a609685d9917efbfb0c37f058c2de88f13d297c7e19e0ab24813079efe57a182554ff054c7638153f9b26a60e7111f71a0ff63d9571704905d3ca6df0b031747";
+    const MAINNET_SIGNATURE: &str = "82f5d3d2de4db19d40a6980e8aa37842a0e55d1df06bd68bddc8d60002e8e959eb9cfa368b3c1b77d18f02a54fe047b80f0989315f83b12a74fd8679c4f12aae86eaf6ab5690b34f1fddd50ee3cc6f6cdf59e95526d5a5d82aaa84fa6f181e42";
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn verify_mainnet_beacon() {
+        let pubkey = from_hex(MAINNET_PUBKEY);
+        let previous_signature = from_hex(MAINNET_PREVIOUS_SIGNATURE);
+        let signature = from_hex(MAINNET_SIGNATURE);
+
+        assert!(verify_beacon(
+            &pubkey,
+            MAINNET_ROUND,
+            &previous_signature,
+            &signature
+        ));
+
+        // the same signature does not pass for a neighbouring round
+        assert!(!verify_beacon(
+            &pubkey,
+            MAINNET_ROUND + 1,
+            &previous_signature,
+            &signature
+        ));
+    }
+
+    #[test]
+    fn verify_signed_beacons() {
+        let genesis = sign(1, b"genesis");
+        let round_2 = sign(2, &genesis);
+
+        assert!(verify_beacon(&pubkey(), 1, b"genesis", &genesis));
+        assert!(verify_beacon(&pubkey(), 2, &genesis, &round_2));
+
+        // wrong round, wrong chain link and garbage bytes all fail
+        assert!(!verify_beacon(&pubkey(), 3, &genesis, &round_2));
+        assert!(!verify_beacon(&pubkey(), 2, b"genesis", &round_2));
+        assert!(!verify_beacon(&pubkey(), 2, &genesis, &[0u8; 96]));
+        assert!(!verify_beacon(&pubkey()[..47], 2, &genesis, &round_2));
+    }
+}
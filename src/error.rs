@@ -30,6 +30,234 @@ pub enum ContractError {
 
     #[error("Given Address Is Not Registered On The Blacklist")]
     NotOnTheBlacklist {},
+
+    #[error("Funds Sent Must Match The Wager Exactly")]
+    InvalidWager {},
+
+    #[error("Move Commitment Must Be A 32 Byte Sha256 Hash")]
+    InvalidCommitment {},
+
+    #[error("Player Is Already Waiting In The Queue")]
+    AlreadyInQueue {},
+
+    #[error("Player Is Not Waiting In The Queue")]
+    NotInQueue {},
+
+    #[error("Revealed Move Does Not Match The Commitment")]
+    CommitmentMismatch {},
+
+    #[error("Salt Too Short")]
+    SaltTooShort {},
+
+    #[error("Move Already Revealed")]
+    AlreadyRevealed {},
+
+    #[error("Reveal Period Has Not Expired Yet")]
+    RevealNotExpired {},
+
+    #[error("Reveal Period Has Expired")]
+    RevealExpired {},
+
+    #[error("Challenge Has Expired")]
+    ChallengeExpired {},
+
+    #[error("Challenge Has Not Expired Yet")]
+    ChallengeNotExpired {},
+
+    #[error("House Mode Is Not Configured")]
+    HouseNotConfigured {},
+
+    #[error("House Bankroll Cannot Cover The Wager")]
+    InsufficientBankroll {},
+
+    #[error("Drand Round Must Be In The Future")]
+    RoundNotInFuture {},
+
+    #[error("Drand Round Is Too Far In The Future")]
+    RoundTooFar {},
+
+    #[error("The Beacon Can Still Be Submitted")]
+    BeaconNotExpired {},
+
+    #[error("Drand Beacon Signature Is Invalid")]
+    InvalidBeacon {},
+
+    #[error("This Game Has Already Been Rematched")]
+    AlreadyRematched {},
+
+    #[error("A Rematch Has Already Been Offered For This Game")]
+    RematchAlreadyOffered {},
+
+    #[error("No Rematch Offer Found")]
+    NoRematchOffer {},
+
+    #[error("A Round Needs At Least Two Distinct Players")]
+    NotEnoughPlayers {},
+
+    #[error("Round Is Not In The {phase} Phase")]
+    WrongRoundPhase { phase: String },
+
+    #[error("Move Already Committed")]
+    AlreadyCommitted {},
+
+    #[error("Round Phase Has Not Expired Yet")]
+    PhaseNotExpired {},
+
+    #[error("Round Phase Has Expired")]
+    PhaseExpired {},
+
+    #[error("King Of The Hill Is Not Configured")]
+    HillNotConfigured {},
+
+    #[error("The Hill Already Has A Champion")]
+    HillOccupied {},
+
+    #[error("The Champion Has Not Committed A Defense Move")]
+    ChampionNotReady {},
+
+    #[error("The Defense Move Is Locked Until The Next Challenge")]
+    CommitmentLocked {},
+
+    #[error("A Challenge Is Already Pending")]
+    ChallengePending {},
+
+    #[error("No Challenge Is Pending")]
+    NoChallenge {},
+
+    #[error("The Champion Cannot Cash Out Yet")]
+    CannotCashOut {},
+
+    #[error("Invalid Signature")]
+    InvalidSignature {},
+
+    #[error("Pubkey Does Not Match Player")]
+    PubkeyMismatch {},
+
+    #[error("Invalid Nonce, Expected {expected}")]
+    InvalidNonce { expected: u64 },
+
+    #[error("Wrong Chain Id")]
+    WrongChainId {},
+
+    #[error("Wrong Contract")]
+    WrongContract {},
+
+    #[error("Operator Not Found")]
+    OperatorNotFound {},
+
+    #[error("Channel Is Not {status}")]
+    WrongChannelStatus { status: String },
+
+    #[error("Channel State Is Older Than The Settled One")]
+    StaleChannelState {},
+
+    #[error("Challenge Period Has Not Ended Yet")]
+    ChallengePeriodNotOver {},
+
+    #[error("Challenge Period Has Ended")]
+    ChallengePeriodOver {},
+
+    #[error("A Channel Round Is Already Disputed")]
+    ChannelRoundDisputed {},
+
+    #[error("No Channel Round Is Disputed")]
+    NoChannelDispute {},
+
+    #[error("Only Unordered Channels Are Supported")]
+    OnlyUnorderedChannel {},
+
+    #[error("Invalid IBC Channel Version {version}")]
+    InvalidIbcVersion { version: String },
+
+    #[error("IBC Channel Not Found")]
+    IbcChannelNotFound {},
+
+    #[error("Bot Not Registered")]
+    BotNotRegistered {},
+
+    #[error("Games Against A Bot Are Played With PlayBotGame")]
+    BotGameRequired {},
+
+    #[error("Emission Schedule Must Be Sorted By Start Time")]
+    InvalidEmissionSchedule {},
+
+    #[error("Insufficient Treasury")]
+    InsufficientTreasury {},
+
+    #[error("Sponsor Bonuses Are A Single Non-Zero Coin")]
+    InvalidSponsorBonus {},
+
+    #[error("Too Many Sponsors")]
+    TooManySponsors {},
+
+    #[error("Side Bets Are Closed")]
+    SideBetsClosed {},
+
+    #[error("Side Bets Take A Single Non-Zero Coin In The Pool Denom")]
+    InvalidSideBet {},
+
+    #[error("Side Bets Are Not Settled Yet")]
+    SideBetsNotSettled {},
+
+    #[error("No Side Bet Found")]
+    NoSideBet {},
+
+    #[error("Side Bet Fee Cannot Exceed 10000 Basis Points")]
+    InvalidSideBetFee {},
+
+    #[error("Ranked Limits Must Be Greater Than Zero")]
+    InvalidRankedConfig {},
+
+    #[error("Nicknames Are 3 To 20 Letters, Digits, '_' Or '-'")]
+    InvalidNickname {},
+
+    #[error("Nickname Taken")]
+    NicknameTaken {},
+
+    #[error("Nickname Not Found")]
+    NicknameNotFound {},
+
+    #[error("Avatar URI Or Bio Too Long")]
+    ProfileTooLong {},
+
+    #[error("Guild Names Are 3 To 32 Letters, Digits, Spaces, '_' Or '-'")]
+    InvalidGuildName {},
+
+    #[error("Guild Name Taken")]
+    GuildNameTaken {},
+
+    #[error("Guild Not Found")]
+    GuildNotFound {},
+
+    #[error("Already In A Guild")]
+    AlreadyInGuild {},
+
+    #[error("Not In A Guild")]
+    NotInGuild {},
+
+    #[error("No Invite From This Guild")]
+    NoGuildInvite {},
+
+    #[error("Guild Is Full")]
+    GuildFull {},
+
+    #[error("Invalid Guild War")]
+    InvalidGuildWar {},
+
+    #[error("Guild War Has Not Ended Yet")]
+    GuildWarNotOver {},
+
+    #[error("A Season Is Already In Progress")]
+    SeasonInProgress {},
+
+    #[error("No Season In Progress")]
+    NoActiveSeason {},
+
+    #[error("Too Many Prizes")]
+    TooManyPrizes {},
+
+    #[error("Funds Do Not Match The Prizes")]
+    InvalidPrizeFunds {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
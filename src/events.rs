@@ -0,0 +1,522 @@
+//! Typed events emitted by the contract.
+//!
+//! Every game emits `rps_game_started` when it is created, then either
+//! `rps_game_resolved` or `rps_game_cancelled`. Bonuses attached to a pending
+//! game or round emit `rps_game_sponsored`. Admin messages emit
+//! `rps_admin_action`. The attribute keys below are stable. Optional values are
+//! left out when they do not apply to a game kind. Coins use the usual
+//! `<amount><denom>` format, moves are `rock`, `paper` or `scissors`, and
+//! results are `host_wins`, `opponent_wins` or `tie`.
+//!
+//! The chain prefixes contract events with `wasm-`. The `parse` functions accept
+//! both forms, so indexers can feed them the events of a transaction as-is.
+
+use std::str::FromStr;
+
+use cosmwasm_std::{Addr, Coin, Event, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::state::{GameMove, GameResult};
+
+pub const GAME_STARTED_EVENT: &str = "rps_game_started";
+pub const GAME_RESOLVED_EVENT: &str = "rps_game_resolved";
+pub const GAME_CANCELLED_EVENT: &str = "rps_game_cancelled";
+pub const GAME_SPONSORED_EVENT: &str = "rps_game_sponsored";
+pub const ADMIN_ACTION_EVENT: &str = "rps_admin_action";
+
+pub const KIND_KEY: &str = "kind";
+pub const GAME_ID_KEY: &str = "game_id";
+pub const RECORD_ID_KEY: &str = "record_id";
+pub const HOST_KEY: &str = "host";
+pub const OPPONENT_KEY: &str = "opponent";
+pub const HOST_MOVE_KEY: &str = "host_move";
+pub const OPP_MOVE_KEY: &str = "opp_move";
+pub const RESULT_KEY: &str = "result";
+pub const STAKE_KEY: &str = "stake";
+pub const FEE_KEY: &str = "fee";
+pub const ACTION_KEY: &str = "action";
+pub const SENDER_KEY: &str = "sender";
+pub const TARGET_KEY: &str = "target";
+pub const SPONSOR_KEY: &str = "sponsor";
+pub const BONUS_KEY: &str = "bonus";
+
+#[derive(Error, Debug, PartialEq)]
+pub enum EventParseError {
+    #[error("Expected Event {expected} But Got {got}")]
+    WrongEventType { expected: String, got: String },
+
+    #[error("Missing Attribute {key}")]
+    MissingAttribute { key: String },
+
+    #[error("Invalid Value {value} For Attribute {key}")]
+    InvalidAttribute { key: String, value: String },
+}
+
+// The kind of game an event is about. Direct games are started with StartGame,
+// queued games come from the matchmaking queue or a rematch, house games are
+// played against drand and hill games are challenges of the king-of-the-hill seat.
+// Rounds are multi-player games, their host is the creator and their opponent the
+// winner. Channel games are whole state channels, player a hosts and the result
+// follows the final balances. Bot games are played against a registered bot
+// contract, which is the opponent. Ibc games are played with another chain and use the
+// host chain's game id, each chain reports its own side of the game.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum GameKind {
+    Direct,
+    Queued,
+    House,
+    Hill,
+    Round,
+    Channel,
+    Ibc,
+    Bot,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameStartedEvent {
+    pub kind: GameKind,
+    pub game_id: Option<u64>,
+    pub host: Addr,
+    pub opponent: Option<Addr>,
+    pub stake: Option<Coin>,
+    pub fee: Option<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameResolvedEvent {
+    pub kind: GameKind,
+    pub game_id: Option<u64>,
+    pub record_id: Option<u64>,
+    pub host: Addr,
+    pub opponent: Option<Addr>,
+    pub host_move: Option<GameMove>,
+    pub opp_move: Option<GameMove>,
+    pub result: GameResult,
+    pub stake: Option<Coin>,
+    pub fee: Option<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameCancelledEvent {
+    pub kind: GameKind,
+    pub game_id: Option<u64>,
+    pub host: Addr,
+    pub opponent: Option<Addr>,
+    pub stake: Option<Coin>,
+}
+
+// The host of a round is its creator.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameSponsoredEvent {
+    pub kind: GameKind,
+    pub game_id: Option<u64>,
+    pub host: Addr,
+    pub opponent: Option<Addr>,
+    pub sponsor: Addr,
+    pub bonus: Coin,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdminActionEvent {
+    pub action: String,
+    pub sender: Addr,
+    pub target: Option<String>,
+}
+
+impl GameStartedEvent {
+    pub fn into_event(self) -> Event {
+        EventBuilder::new(GAME_STARTED_EVENT)
+            .add(KIND_KEY, kind_str(self.kind))
+            .add_opt(GAME_ID_KEY, self.game_id)
+            .add(HOST_KEY, self.host)
+            .add_opt(OPPONENT_KEY, self.opponent)
+            .add_opt(STAKE_KEY, self.stake)
+            .add_opt(FEE_KEY, self.fee)
+            .event
+    }
+
+    pub fn parse(event: &Event) -> Result<Self, EventParseError> {
+        let attrs = EventAttributes::new(event, GAME_STARTED_EVENT)?;
+        Ok(GameStartedEvent {
+            kind: attrs.require(KIND_KEY, parse_kind)?,
+            game_id: attrs.optional(GAME_ID_KEY, parse_from_str)?,
+            host: attrs.require(HOST_KEY, parse_addr)?,
+            opponent: attrs.optional(OPPONENT_KEY, parse_addr)?,
+            stake: attrs.optional(STAKE_KEY, parse_coin)?,
+            fee: attrs.optional(FEE_KEY, parse_coin)?,
+        })
+    }
+}
+
+impl GameResolvedEvent {
+    pub fn into_event(self) -> Event {
+        EventBuilder::new(GAME_RESOLVED_EVENT)
+            .add(KIND_KEY, kind_str(self.kind))
+            .add_opt(GAME_ID_KEY, self.game_id)
+            .add_opt(RECORD_ID_KEY, self.record_id)
+            .add(HOST_KEY, self.host)
+            .add_opt(OPPONENT_KEY, self.opponent)
+            .add_opt(HOST_MOVE_KEY, self.host_move.map(move_str))
+            .add_opt(OPP_MOVE_KEY, self.opp_move.map(move_str))
+            .add(RESULT_KEY, result_str(self.result))
+            .add_opt(STAKE_KEY, self.stake)
+            .add_opt(FEE_KEY, self.fee)
+            .event
+    }
+
+    pub fn parse(event: &Event) -> Result<Self, EventParseError> {
+        let attrs = EventAttributes::new(event, GAME_RESOLVED_EVENT)?;
+        Ok(GameResolvedEvent {
+            kind: attrs.require(KIND_KEY, parse_kind)?,
+            game_id: attrs.optional(GAME_ID_KEY, parse_from_str)?,
+            record_id: attrs.optional(RECORD_ID_KEY, parse_from_str)?,
+            host: attrs.require(HOST_KEY, parse_addr)?,
+            opponent: attrs.optional(OPPONENT_KEY, parse_addr)?,
+            host_move: attrs.optional(HOST_MOVE_KEY, parse_move)?,
+            opp_move: attrs.optional(OPP_MOVE_KEY, parse_move)?,
+            result: attrs.require(RESULT_KEY, parse_result)?,
+            stake: attrs.optional(STAKE_KEY, parse_coin)?,
+            fee: attrs.optional(FEE_KEY, parse_coin)?,
+        })
+    }
+}
+
+impl GameCancelledEvent {
+    pub fn into_event(self) -> Event {
+        EventBuilder::new(GAME_CANCELLED_EVENT)
+            .add(KIND_KEY, kind_str(self.kind))
+            .add_opt(GAME_ID_KEY, self.game_id)
+            .add(HOST_KEY, self.host)
+            .add_opt(OPPONENT_KEY, self.opponent)
+            .add_opt(STAKE_KEY, self.stake)
+            .event
+    }
+
+    pub fn parse(event: &Event) -> Result<Self, EventParseError> {
+        let attrs = EventAttributes::new(event, GAME_CANCELLED_EVENT)?;
+        Ok(GameCancelledEvent {
+            kind: attrs.require(KIND_KEY, parse_kind)?,
+            game_id: attrs.optional(GAME_ID_KEY, parse_from_str)?,
+            host: attrs.require(HOST_KEY, parse_addr)?,
+            opponent: attrs.optional(OPPONENT_KEY, parse_addr)?,
+            stake: attrs.optional(STAKE_KEY, parse_coin)?,
+        })
+    }
+}
+
+impl GameSponsoredEvent {
+    pub fn into_event(self) -> Event {
+        EventBuilder::new(GAME_SPONSORED_EVENT)
+            .add(KIND_KEY, kind_str(self.kind))
+            .add_opt(GAME_ID_KEY, self.game_id)
+            .add(HOST_KEY, self.host)
+            .add_opt(OPPONENT_KEY, self.opponent)
+            .add(SPONSOR_KEY, self.sponsor)
+            .add(BONUS_KEY, self.bonus)
+            .event
+    }
+
+    pub fn parse(event: &Event) -> Result<Self, EventParseError> {
+        let attrs = EventAttributes::new(event, GAME_SPONSORED_EVENT)?;
+        Ok(GameSponsoredEvent {
+            kind: attrs.require(KIND_KEY, parse_kind)?,
+            game_id: attrs.optional(GAME_ID_KEY, parse_from_str)?,
+            host: attrs.require(HOST_KEY, parse_addr)?,
+            opponent: attrs.optional(OPPONENT_KEY, parse_addr)?,
+            sponsor: attrs.require(SPONSOR_KEY, parse_addr)?,
+            bonus: attrs.require(BONUS_KEY, parse_coin)?,
+        })
+    }
+}
+
+impl AdminActionEvent {
+    pub fn new(action: &str, sender: &Addr, target: Option<String>) -> Self {
+        AdminActionEvent {
+            action: action.to_string(),
+            sender: sender.clone(),
+            target,
+        }
+    }
+
+    pub fn into_event(self) -> Event {
+        EventBuilder::new(ADMIN_ACTION_EVENT)
+            .add(ACTION_KEY, self.action)
+            .add(SENDER_KEY, self.sender)
+            .add_opt(TARGET_KEY, self.target)
+            .event
+    }
+
+    pub fn parse(event: &Event) -> Result<Self, EventParseError> {
+        let attrs = EventAttributes::new(event, ADMIN_ACTION_EVENT)?;
+        Ok(AdminActionEvent {
+            action: attrs.require(ACTION_KEY, |v| Some(v.to_string()))?,
+            sender: attrs.require(SENDER_KEY, parse_addr)?,
+            target: attrs.optional(TARGET_KEY, |v| Some(v.to_string()))?,
+        })
+    }
+}
+
+pub fn move_str(player_move: GameMove) -> &'static str {
+    match player_move {
+        GameMove::Rock => "rock",
+        GameMove::Paper => "paper",
+        GameMove::Scissors => "scissors",
+    }
+}
+
+pub fn result_str(result: GameResult) -> &'static str {
+    match result {
+        GameResult::HostWins => "host_wins",
+        GameResult::OpponentWins => "opponent_wins",
+        GameResult::Tie => "tie",
+    }
+}
+
+fn kind_str(kind: GameKind) -> &'static str {
+    match kind {
+        GameKind::Direct => "direct",
+        GameKind::Queued => "queued",
+        GameKind::House => "house",
+        GameKind::Hill => "hill",
+        GameKind::Round => "round",
+        GameKind::Channel => "channel",
+        GameKind::Ibc => "ibc",
+        GameKind::Bot => "bot",
+    }
+}
+
+fn parse_kind(value: &str) -> Option<GameKind> {
+    match value {
+        "direct" => Some(GameKind::Direct),
+        "queued" => Some(GameKind::Queued),
+        "house" => Some(GameKind::House),
+        "hill" => Some(GameKind::Hill),
+        "round" => Some(GameKind::Round),
+        "channel" => Some(GameKind::Channel),
+        "ibc" => Some(GameKind::Ibc),
+        "bot" => Some(GameKind::Bot),
+        _ => None,
+    }
+}
+
+fn parse_move(value: &str) -> Option<GameMove> {
+    match value {
+        "rock" => Some(GameMove::Rock),
+        "paper" => Some(GameMove::Paper),
+        "scissors" => Some(GameMove::Scissors),
+        _ => None,
+    }
+}
+
+fn parse_result(value: &str) -> Option<GameResult> {
+    match value {
+        "host_wins" => Some(GameResult::HostWins),
+        "opponent_wins" => Some(GameResult::OpponentWins),
+        "tie" => Some(GameResult::Tie),
+        _ => None,
+    }
+}
+
+fn parse_addr(value: &str) -> Option<Addr> {
+    Some(Addr::unchecked(value))
+}
+
+fn parse_from_str<T: FromStr>(value: &str) -> Option<T> {
+    value.parse().ok()
+}
+
+fn parse_coin(value: &str) -> Option<Coin> {
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, denom) = value.split_at(split);
+    Some(Coin {
+        denom: denom.to_string(),
+        amount: Uint128::from_str(amount).ok()?,
+    })
+}
+
+struct EventBuilder {
+    event: Event,
+}
+
+impl EventBuilder {
+    fn new(ty: &str) -> Self {
+        EventBuilder {
+            event: Event::new(ty),
+        }
+    }
+
+    fn add(mut self, key: &str, value: impl ToString) -> Self {
+        self.event = self.event.add_attribute(key, value.to_string());
+        self
+    }
+
+    fn add_opt(self, key: &str, value: Option<impl ToString>) -> Self {
+        match value {
+            Some(value) => self.add(key, value),
+            None => self,
+        }
+    }
+}
+
+struct EventAttributes<'a> {
+    event: &'a Event,
+}
+
+impl<'a> EventAttributes<'a> {
+    fn new(event: &'a Event, expected: &str) -> Result<Self, EventParseError> {
+        let ty = event.ty.strip_prefix("wasm-").unwrap_or(&event.ty);
+        if ty != expected {
+            return Err(EventParseError::WrongEventType {
+                expected: expected.to_string(),
+                got: event.ty.clone(),
+            });
+        }
+
+        Ok(EventAttributes { event })
+    }
+
+    fn optional<T>(
+        &self,
+        key: &str,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> Result<Option<T>, EventParseError> {
+        match self.event.attributes.iter().find(|attr| attr.key == key) {
+            Some(attr) => match parse(&attr.value) {
+                Some(value) => Ok(Some(value)),
+                None => Err(EventParseError::InvalidAttribute {
+                    key: key.to_string(),
+                    value: attr.value.clone(),
+                }),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn require<T>(
+        &self,
+        key: &str,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> Result<T, EventParseError> {
+        self.optional(key, parse)?
+            .ok_or_else(|| EventParseError::MissingAttribute {
+                key: key.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::coin;
+
+    #[test]
+    fn round_trip() {
+        let resolved = GameResolvedEvent {
+            kind: GameKind::Queued,
+            game_id: Some(3),
+            record_id: Some(8),
+            host: Addr::unchecked("hosty"),
+            opponent: Some(Addr::unchecked("toasty")),
+            host_move: Some(GameMove::Rock),
+            opp_move: None,
+            result: GameResult::HostWins,
+            stake: Some(coin(10, "token")),
+            fee: None,
+        };
+        let event = resolved.clone().into_event();
+        assert_eq!("rps_game_resolved", event.ty);
+        assert_eq!(Ok(resolved), GameResolvedEvent::parse(&event));
+
+        let started = GameStartedEvent {
+            kind: GameKind::Hill,
+            game_id: None,
+            host: Addr::unchecked("champion"),
+            opponent: Some(Addr::unchecked("challenger")),
+            stake: None,
+            fee: Some(coin(
+                5,
+                "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2",
+            )),
+        };
+        assert_eq!(
+            Ok(started.clone()),
+            GameStartedEvent::parse(&started.into_event())
+        );
+
+        let cancelled = GameCancelledEvent {
+            kind: GameKind::Ibc,
+            game_id: Some(4),
+            host: Addr::unchecked("osmo1host"),
+            opponent: Some(Addr::unchecked("juno1opponent")),
+            stake: Some(coin(10, "token")),
+        };
+        assert_eq!(
+            Ok(cancelled.clone()),
+            GameCancelledEvent::parse(&cancelled.into_event())
+        );
+
+        let sponsored = GameSponsoredEvent {
+            kind: GameKind::Round,
+            game_id: Some(2),
+            host: Addr::unchecked("alice"),
+            opponent: None,
+            sponsor: Addr::unchecked("acme"),
+            bonus: coin(50, "token"),
+        };
+        assert_eq!(
+            Ok(sponsored.clone()),
+            GameSponsoredEvent::parse(&sponsored.into_event())
+        );
+    }
+
+    #[test]
+    fn parse_chain_events() {
+        // events as they come back from the chain, prefixed and with extra attributes
+        let event = Event::new("wasm-rps_game_cancelled")
+            .add_attribute("_contract_address", "terra1contract")
+            .add_attribute("kind", "direct")
+            .add_attribute("host", "hosty")
+            .add_attribute("opponent", "toasty");
+        assert_eq!(
+            Ok(GameCancelledEvent {
+                kind: GameKind::Direct,
+                game_id: None,
+                host: Addr::unchecked("hosty"),
+                opponent: Some(Addr::unchecked("toasty")),
+                stake: None,
+            }),
+            GameCancelledEvent::parse(&event)
+        );
+
+        assert_eq!(
+            Err(EventParseError::WrongEventType {
+                expected: "rps_game_started".to_string(),
+                got: "wasm-rps_game_cancelled".to_string(),
+            }),
+            GameStartedEvent::parse(&event)
+        );
+
+        let event = Event::new("rps_game_resolved")
+            .add_attribute("kind", "direct")
+            .add_attribute("host", "hosty")
+            .add_attribute("result", "Host Wins");
+        assert_eq!(
+            Err(EventParseError::InvalidAttribute {
+                key: "result".to_string(),
+                value: "Host Wins".to_string(),
+            }),
+            GameResolvedEvent::parse(&event)
+        );
+
+        let event = Event::new("rps_admin_action").add_attribute("action", "update_admin");
+        assert_eq!(
+            Err(EventParseError::MissingAttribute {
+                key: "sender".to_string(),
+            }),
+            AdminActionEvent::parse(&event)
+        );
+    }
+}
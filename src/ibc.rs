@@ -0,0 +1,1132 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, Event,
+    IbcBasicResponse, IbcChannel, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+    IbcMsg, IbcOrder, IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+    IbcReceiveResponse, IbcTimeout, MessageInfo, Order, Response, StdError, StdResult, Timestamp,
+};
+use cw0::Duration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::contract::{check_blacklist, check_wager_funds, get_game_result, verify_move};
+use crate::error::ContractError;
+use crate::events::{
+    result_str, GameCancelledEvent, GameKind, GameResolvedEvent, GameStartedEvent,
+};
+use crate::state::{
+    GameMove, GameResult, IbcGame, IbcGameStatus, IbcRole, IBC_CHANNELS, IBC_GAMES, IBC_GAME_SEQ,
+};
+
+// channel version both deployments must agree on, bumped with any packet format change
+pub const IBC_VERSION: &str = "rps-1";
+
+// how long a packet may take to reach the other chain
+const IBC_PACKET_TIMEOUT: u64 = 60 * 60;
+// how long the host has to reveal after the opponent's move arrived
+const IBC_REVEAL_TIMEOUT: Duration = Duration::Time(60 * 60);
+// how long the opponent has to answer a challenge
+const IBC_RESPONSE_TIMEOUT: u64 = 60 * 60;
+
+// Packets exchanged by two deployments over a channel of version IBC_VERSION.
+// Game ids are always the id on the host chain.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RpsPacket {
+    // host chain -> opponent chain
+    Challenge {
+        game_id: u64,
+        host: String,
+        opponent: String,
+        host_commitment: Binary,
+        host_payout: Option<String>,
+        opponent_stake: Option<Coin>,
+        respond_by: Timestamp,
+    },
+    // opponent chain -> host chain
+    Move {
+        game_id: u64,
+        opp_move: GameMove,
+        opponent_payout: Option<String>,
+    },
+    // host chain -> opponent chain, the opponent chain checks the reveal itself
+    Result {
+        game_id: u64,
+        host_move: GameMove,
+        salt: String,
+    },
+    // opponent chain -> host chain, the host chain decides if the reveal window is over
+    ClaimTimeout {
+        game_id: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RpsAck {
+    Result(Binary),
+    Error(String),
+}
+
+fn ack_success() -> Binary {
+    to_binary(&RpsAck::Result(Binary::from(b"1"))).unwrap()
+}
+
+fn ack_fail(err: String) -> Binary {
+    to_binary(&RpsAck::Error(err)).unwrap()
+}
+
+fn check_channel(channel: &IbcChannel) -> Result<(), ContractError> {
+    if channel.order != IbcOrder::Unordered {
+        return Err(ContractError::OnlyUnorderedChannel {});
+    }
+
+    if channel.version != IBC_VERSION {
+        return Err(ContractError::InvalidIbcVersion {
+            version: channel.version.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> Result<(), ContractError> {
+    check_channel(msg.channel())?;
+
+    if let Some(version) = msg.counterparty_version() {
+        if version != IBC_VERSION {
+            return Err(ContractError::InvalidIbcVersion {
+                version: version.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel = msg.channel();
+    check_channel(channel)?;
+
+    IBC_CHANNELS.save(
+        deps.storage,
+        &channel.endpoint.channel_id,
+        &channel.counterparty_endpoint,
+    )?;
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("method", "ibc_channel_connect")
+        .add_attribute("channel_id", &channel.endpoint.channel_id))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelCloseMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel = msg.channel();
+    let channel_id = &channel.endpoint.channel_id;
+    IBC_CHANNELS.remove(deps.storage, channel_id);
+
+    //no packet can settle the open games any more, so each side refunds its own escrow
+    let games = IBC_GAMES
+        .prefix(channel_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut res = IbcBasicResponse::new();
+    for (game_id, game) in games {
+        IBC_GAMES.remove(deps.storage, (channel_id, game_id));
+        res = res
+            .add_event(ibc_cancelled_event(&game))
+            .add_messages(refund_ibc_game(&game));
+    }
+
+    Ok(res
+        .add_attribute("method", "ibc_channel_close")
+        .add_attribute("channel_id", channel_id))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, ContractError> {
+    //a rejected packet is answered with an error ack, so the sender can release its escrow
+    do_ibc_packet_receive(deps, env, &msg.packet).or_else(|err| {
+        Ok(IbcReceiveResponse::new()
+            .set_ack(ack_fail(err.to_string()))
+            .add_attribute("method", "ibc_packet_receive")
+            .add_attribute("error", err.to_string()))
+    })
+}
+
+fn do_ibc_packet_receive(
+    deps: DepsMut,
+    env: Env,
+    packet: &IbcPacket,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let channel_id = packet.dest.channel_id.as_str();
+    if !IBC_CHANNELS.has(deps.storage, channel_id) {
+        return Err(ContractError::IbcChannelNotFound {});
+    }
+
+    let res = IbcReceiveResponse::new().add_attribute("method", "ibc_packet_receive");
+
+    match from_binary(&packet.data)? {
+        RpsPacket::Challenge {
+            game_id,
+            host,
+            opponent,
+            host_commitment,
+            host_payout,
+            opponent_stake,
+            respond_by,
+        } => {
+            let opponent = deps.api.addr_validate(&opponent)?;
+            check_blacklist(deps.as_ref(), &opponent)?;
+
+            if IBC_GAMES.has(deps.storage, (channel_id, game_id)) {
+                return Err(ContractError::OneGameAtATime {});
+            }
+
+            //the host is paid from the opponent's escrow here, so it needs a local address
+            let payout = match (&opponent_stake, host_payout) {
+                (Some(_), Some(payout)) => Some(deps.api.addr_validate(&payout)?.to_string()),
+                (None, _) => None,
+                (Some(_), None) => return Err(ContractError::InvalidWager {}),
+            };
+
+            let game = IbcGame {
+                channel_id: channel_id.to_string(),
+                game_id,
+                role: IbcRole::Opponent,
+                host,
+                opponent: opponent.to_string(),
+                host_commitment,
+                host_move: None,
+                opp_move: None,
+                stake: None,
+                opponent_stake,
+                payout,
+                status: IbcGameStatus::Challenged,
+                reveal_expires: None,
+                respond_by: Some(respond_by),
+            };
+            IBC_GAMES.save(deps.storage, (channel_id, game_id), &game)?;
+
+            Ok(res
+                .set_ack(ack_success())
+                .add_event(ibc_started_event(&game))
+                .add_attribute("packet", "challenge")
+                .add_attribute("game_id", game_id.to_string())
+                .add_attribute("opponent", opponent))
+        }
+        RpsPacket::Move {
+            game_id,
+            opp_move,
+            opponent_payout,
+        } => {
+            let mut game = load_ibc_game(
+                deps.as_ref(),
+                channel_id,
+                game_id,
+                IbcRole::Host,
+                IbcGameStatus::Challenged,
+            )?;
+
+            if game.stake.is_some() {
+                let payout = opponent_payout.ok_or(ContractError::InvalidWager {})?;
+                game.payout = Some(deps.api.addr_validate(&payout)?.to_string());
+            }
+
+            game.opp_move = Some(opp_move);
+            game.status = IbcGameStatus::Moved;
+            game.reveal_expires = Some(IBC_REVEAL_TIMEOUT.after(&env.block));
+            IBC_GAMES.save(deps.storage, (channel_id, game_id), &game)?;
+
+            Ok(res
+                .set_ack(ack_success())
+                .add_attribute("packet", "move")
+                .add_attribute("game_id", game_id.to_string()))
+        }
+        RpsPacket::Result {
+            game_id,
+            host_move,
+            salt,
+        } => {
+            let mut game = load_ibc_game(
+                deps.as_ref(),
+                channel_id,
+                game_id,
+                IbcRole::Opponent,
+                IbcGameStatus::Moved,
+            )?;
+
+            verify_move(host_move, &salt, &game.host_commitment)?;
+
+            game.host_move = Some(host_move);
+            let result = get_game_result(host_move, game.opp_move.unwrap_or(host_move))?;
+            IBC_GAMES.remove(deps.storage, (channel_id, game_id));
+
+            Ok(res
+                .set_ack(ack_success())
+                .add_event(ibc_resolved_event(&game, result))
+                .add_messages(settle_ibc_game(&game, result))
+                .add_attribute("packet", "result")
+                .add_attribute("game_id", game_id.to_string())
+                .add_attribute("result", result_str(result)))
+        }
+        RpsPacket::ClaimTimeout { game_id } => {
+            let game = load_ibc_game(
+                deps.as_ref(),
+                channel_id,
+                game_id,
+                IbcRole::Host,
+                IbcGameStatus::Moved,
+            )?;
+
+            if !game
+                .reveal_expires
+                .is_some_and(|expires| expires.is_expired(&env.block))
+            {
+                return Err(ContractError::RevealNotExpired {});
+            }
+
+            //a host who does not reveal in time forfeits
+            let result = GameResult::OpponentWins;
+            IBC_GAMES.remove(deps.storage, (channel_id, game_id));
+
+            Ok(res
+                .set_ack(to_binary(&RpsAck::Result(to_binary(&result)?))?)
+                .add_event(ibc_resolved_event(&game, result))
+                .add_messages(settle_ibc_game(&game, result))
+                .add_attribute("packet", "claim_timeout")
+                .add_attribute("game_id", game_id.to_string())
+                .add_attribute("result", result_str(result)))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketAckMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let ack: RpsAck = from_binary(&msg.acknowledgement.data)?;
+    let packet = msg.original_packet;
+
+    match ack {
+        RpsAck::Result(data) => on_packet_success(deps, &packet, data),
+        RpsAck::Error(err) => {
+            on_packet_failure(deps, env, &packet).map(|res| res.add_attribute("error", err))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketTimeoutMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    on_packet_failure(deps, env, &msg.packet)
+}
+
+fn on_packet_success(
+    deps: DepsMut,
+    packet: &IbcPacket,
+    data: Binary,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel_id = packet.src.channel_id.as_str();
+    let res = IbcBasicResponse::new().add_attribute("method", "ibc_packet_ack");
+
+    match from_binary(&packet.data)? {
+        //the host chain forfeited the game, release the opponent's escrow
+        RpsPacket::ClaimTimeout { game_id } => {
+            let result: GameResult = from_binary(&data)?;
+            let game = IBC_GAMES.load(deps.storage, (channel_id, game_id))?;
+            IBC_GAMES.remove(deps.storage, (channel_id, game_id));
+
+            Ok(res
+                .add_event(ibc_resolved_event(&game, result))
+                .add_messages(settle_ibc_game(&game, result))
+                .add_attribute("game_id", game_id.to_string())
+                .add_attribute("result", result_str(result)))
+        }
+        _ => Ok(res),
+    }
+}
+
+fn on_packet_failure(
+    deps: DepsMut,
+    env: Env,
+    packet: &IbcPacket,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel_id = packet.src.channel_id.as_str();
+    let res = IbcBasicResponse::new().add_attribute("method", "ibc_packet_failed");
+
+    match from_binary(&packet.data)? {
+        //the game never started on the other chain, refund the local player
+        RpsPacket::Challenge { game_id, .. } | RpsPacket::Move { game_id, .. } => {
+            let game = match IBC_GAMES.may_load(deps.storage, (channel_id, game_id))? {
+                Some(game) => game,
+                None => return Ok(res),
+            };
+            IBC_GAMES.remove(deps.storage, (channel_id, game_id));
+
+            Ok(res
+                .add_event(ibc_cancelled_event(&game))
+                .add_messages(refund_ibc_game(&game))
+                .add_attribute("game_id", game_id.to_string()))
+        }
+        //the host chain already paid out, the opponent chain must still learn the result
+        RpsPacket::Result { game_id, .. } => Ok(res
+            .add_message(send_packet(&env, channel_id, packet.data.clone()))
+            .add_attribute("game_id", game_id.to_string())
+            .add_attribute("resent", "result")),
+        //nothing changed on the host chain, the claim can be sent again
+        RpsPacket::ClaimTimeout { game_id } => {
+            Ok(res.add_attribute("game_id", game_id.to_string()))
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn try_ibc_challenge(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: String,
+    opponent: String,
+    move_commitment: Binary,
+    host_payout: Option<String>,
+    opponent_stake: Option<Coin>,
+) -> Result<Response, ContractError> {
+    check_blacklist(deps.as_ref(), &info.sender)?;
+
+    if !IBC_CHANNELS.has(deps.storage, &channel_id) {
+        return Err(ContractError::IbcChannelNotFound {});
+    }
+
+    if move_commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    //either both players stake or neither does
+    let stake = match (info.funds.as_slice(), &opponent_stake, &host_payout) {
+        ([], None, _) => None,
+        ([stake], Some(opponent_stake), Some(_))
+            if !stake.amount.is_zero() && !opponent_stake.amount.is_zero() =>
+        {
+            Some(stake.clone())
+        }
+        _ => return Err(ContractError::InvalidWager {}),
+    };
+
+    let game_id = IBC_GAME_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    IBC_GAME_SEQ.save(deps.storage, &game_id)?;
+
+    let respond_by = env.block.time.plus_seconds(IBC_RESPONSE_TIMEOUT);
+
+    let game = IbcGame {
+        channel_id: channel_id.clone(),
+        game_id,
+        role: IbcRole::Host,
+        host: info.sender.to_string(),
+        opponent: opponent.clone(),
+        host_commitment: move_commitment.clone(),
+        host_move: None,
+        opp_move: None,
+        stake,
+        opponent_stake: opponent_stake.clone(),
+        payout: None,
+        status: IbcGameStatus::Challenged,
+        reveal_expires: None,
+        respond_by: Some(respond_by),
+    };
+    IBC_GAMES.save(deps.storage, (&channel_id, game_id), &game)?;
+
+    let packet = RpsPacket::Challenge {
+        game_id,
+        host: info.sender.to_string(),
+        opponent: opponent.clone(),
+        host_commitment: move_commitment,
+        host_payout,
+        opponent_stake,
+        respond_by,
+    };
+
+    Ok(Response::new()
+        .add_event(ibc_started_event(&game))
+        .add_message(send_packet(&env, &channel_id, to_binary(&packet)?))
+        .add_attribute("method", "try_ibc_challenge")
+        .add_attribute("channel_id", channel_id)
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("host", info.sender)
+        .add_attribute("opponent", opponent))
+}
+
+pub fn try_ibc_cancel_challenge(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: String,
+    game_id: u64,
+) -> Result<Response, ContractError> {
+    let game = load_ibc_game(
+        deps.as_ref(),
+        &channel_id,
+        game_id,
+        IbcRole::Host,
+        IbcGameStatus::Challenged,
+    )?;
+
+    if info.sender != game.host {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    //a move sent before respond_by times out before this, so the host cannot
+    //cancel once it has seen the opponent's move on the other chain
+    if let Some(respond_by) = game.respond_by {
+        if env.block.time < respond_by.plus_seconds(IBC_PACKET_TIMEOUT) {
+            return Err(ContractError::ChallengeNotExpired {});
+        }
+    }
+
+    IBC_GAMES.remove(deps.storage, (&channel_id, game_id));
+
+    Ok(Response::new()
+        .add_event(ibc_cancelled_event(&game))
+        .add_messages(refund_ibc_game(&game))
+        .add_attribute("method", "try_ibc_cancel_challenge")
+        .add_attribute("game_id", game_id.to_string()))
+}
+
+pub fn try_ibc_respond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: String,
+    game_id: u64,
+    opp_move: GameMove,
+    opponent_payout: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut game = load_ibc_game(
+        deps.as_ref(),
+        &channel_id,
+        game_id,
+        IbcRole::Opponent,
+        IbcGameStatus::Challenged,
+    )?;
+
+    if info.sender != game.opponent {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if game
+        .respond_by
+        .is_some_and(|respond_by| env.block.time > respond_by)
+    {
+        return Err(ContractError::ChallengeExpired {});
+    }
+
+    check_wager_funds(&info, &game.opponent_stake)?;
+    if game.opponent_stake.is_some() && opponent_payout.is_none() {
+        return Err(ContractError::InvalidWager {});
+    }
+
+    game.stake = game.opponent_stake.clone();
+    game.opp_move = Some(opp_move);
+    game.status = IbcGameStatus::Moved;
+    IBC_GAMES.save(deps.storage, (&channel_id, game_id), &game)?;
+
+    let packet = RpsPacket::Move {
+        game_id,
+        opp_move,
+        opponent_payout,
+    };
+
+    Ok(Response::new()
+        .add_message(send_packet(&env, &channel_id, to_binary(&packet)?))
+        .add_attribute("method", "try_ibc_respond")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("opponent", info.sender))
+}
+
+pub fn try_ibc_reveal_move(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: String,
+    game_id: u64,
+    host_move: GameMove,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let mut game = load_ibc_game(
+        deps.as_ref(),
+        &channel_id,
+        game_id,
+        IbcRole::Host,
+        IbcGameStatus::Moved,
+    )?;
+
+    if info.sender != game.host {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    verify_move(host_move, &salt, &game.host_commitment)?;
+
+    game.host_move = Some(host_move);
+    let result = get_game_result(host_move, game.opp_move.unwrap_or(host_move))?;
+    IBC_GAMES.remove(deps.storage, (&channel_id, game_id));
+
+    let packet = RpsPacket::Result {
+        game_id,
+        host_move,
+        salt,
+    };
+
+    Ok(Response::new()
+        .add_event(ibc_resolved_event(&game, result))
+        .add_messages(settle_ibc_game(&game, result))
+        .add_message(send_packet(&env, &channel_id, to_binary(&packet)?))
+        .add_attribute("method", "try_ibc_reveal_move")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("result", result_str(result)))
+}
+
+pub fn try_ibc_claim_timeout(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: String,
+    game_id: u64,
+) -> Result<Response, ContractError> {
+    let game = load_ibc_game(
+        deps.as_ref(),
+        &channel_id,
+        game_id,
+        IbcRole::Opponent,
+        IbcGameStatus::Moved,
+    )?;
+
+    if info.sender != game.opponent {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    //the host chain checks its own reveal window and answers in the ack
+    let packet = RpsPacket::ClaimTimeout { game_id };
+
+    Ok(Response::new()
+        .add_message(send_packet(&env, &channel_id, to_binary(&packet)?))
+        .add_attribute("method", "try_ibc_claim_timeout")
+        .add_attribute("game_id", game_id.to_string()))
+}
+
+fn load_ibc_game(
+    deps: Deps,
+    channel_id: &str,
+    game_id: u64,
+    role: IbcRole,
+    status: IbcGameStatus,
+) -> Result<IbcGame, ContractError> {
+    let game = IBC_GAMES
+        .may_load(deps.storage, (channel_id, game_id))?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if game.role != role || game.status != status {
+        return Err(StdError::generic_err(format!(
+            "game is not a {:?} game in the {:?} state",
+            role, status
+        ))
+        .into());
+    }
+
+    Ok(game)
+}
+
+fn send_packet(env: &Env, channel_id: &str, data: Binary) -> IbcMsg {
+    IbcMsg::SendPacket {
+        channel_id: channel_id.to_string(),
+        data,
+        timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(IBC_PACKET_TIMEOUT)),
+    }
+}
+
+fn local_player(game: &IbcGame) -> &str {
+    match game.role {
+        IbcRole::Host => &game.host,
+        IbcRole::Opponent => &game.opponent,
+    }
+}
+
+// the stake the local player escrows on this chain
+fn ibc_local_stake(game: &IbcGame) -> Option<Coin> {
+    match game.role {
+        IbcRole::Host => game.stake.clone(),
+        IbcRole::Opponent => game.opponent_stake.clone(),
+    }
+}
+
+// players may live on either chain, so their addresses are not validated here
+fn ibc_started_event(game: &IbcGame) -> Event {
+    GameStartedEvent {
+        kind: GameKind::Ibc,
+        game_id: Some(game.game_id),
+        host: Addr::unchecked(&game.host),
+        opponent: Some(Addr::unchecked(&game.opponent)),
+        stake: ibc_local_stake(game),
+        fee: None,
+    }
+    .into_event()
+}
+
+fn ibc_cancelled_event(game: &IbcGame) -> Event {
+    GameCancelledEvent {
+        kind: GameKind::Ibc,
+        game_id: Some(game.game_id),
+        host: Addr::unchecked(&game.host),
+        opponent: Some(Addr::unchecked(&game.opponent)),
+        stake: ibc_local_stake(game),
+    }
+    .into_event()
+}
+
+fn ibc_resolved_event(game: &IbcGame, result: GameResult) -> Event {
+    GameResolvedEvent {
+        kind: GameKind::Ibc,
+        game_id: Some(game.game_id),
+        record_id: None,
+        host: Addr::unchecked(&game.host),
+        opponent: Some(Addr::unchecked(&game.opponent)),
+        host_move: game.host_move,
+        opp_move: game.opp_move,
+        result,
+        stake: ibc_local_stake(game),
+        fee: None,
+    }
+    .into_event()
+}
+
+fn refund_ibc_game(game: &IbcGame) -> Vec<BankMsg> {
+    match &game.stake {
+        Some(stake) => vec![BankMsg::Send {
+            to_address: local_player(game).to_string(),
+            amount: vec![stake.clone()],
+        }],
+        None => vec![],
+    }
+}
+
+// the local stake goes back to the local player unless it lost, then to the winner's payout address
+fn settle_ibc_game(game: &IbcGame, result: GameResult) -> Vec<BankMsg> {
+    let lost = matches!(
+        (&game.role, result),
+        (IbcRole::Host, GameResult::OpponentWins) | (IbcRole::Opponent, GameResult::HostWins)
+    );
+
+    match (&game.stake, &game.payout) {
+        (Some(stake), Some(payout)) if lost => vec![BankMsg::Send {
+            to_address: payout.clone(),
+            amount: vec![stake.clone()],
+        }],
+        _ => refund_ibc_game(game),
+    }
+}
+
+pub fn query_ibc_channels(deps: Deps) -> StdResult<Vec<String>> {
+    IBC_CHANNELS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{commit_move, execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_ibc_channel_close_confirm, mock_ibc_channel_close_init,
+        mock_ibc_channel_connect_ack, mock_ibc_channel_open_try, mock_ibc_packet_ack,
+        mock_ibc_packet_recv, mock_ibc_packet_timeout, mock_info, MockApi, MockQuerier,
+        MockStorage,
+    };
+    use cosmwasm_std::{coin, coins, Addr, CosmosMsg, IbcAcknowledgement, OwnedDeps};
+
+    type Deployment = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    fn setup(channel_id: &str) -> Deployment {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Addr::unchecked("creator"),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let open = mock_ibc_channel_open_try(channel_id, IbcOrder::Unordered, IBC_VERSION);
+        ibc_channel_open(deps.as_mut(), mock_env(), open).unwrap();
+        let connect = mock_ibc_channel_connect_ack(channel_id, IbcOrder::Unordered, IBC_VERSION);
+        ibc_channel_connect(deps.as_mut(), mock_env(), connect).unwrap();
+
+        deps
+    }
+
+    fn sent_packet(res: &Response) -> RpsPacket {
+        res.messages
+            .iter()
+            .find_map(|sub| match &sub.msg {
+                CosmosMsg::Ibc(IbcMsg::SendPacket { data, .. }) => from_binary(data).ok(),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    // delivers the packet to the other deployment and hands its ack back to the sender
+    fn relay(
+        packet: &RpsPacket,
+        from: (&mut Deployment, &str),
+        to: (&mut Deployment, &str),
+        env: Env,
+    ) -> (IbcReceiveResponse, IbcBasicResponse) {
+        let recv = mock_ibc_packet_recv(to.1, packet).unwrap();
+        let received = ibc_packet_receive(to.0.as_mut(), env, recv).unwrap();
+
+        let ack = IbcAcknowledgement::new(received.acknowledgement.clone());
+        let ack = mock_ibc_packet_ack(from.1, packet, ack).unwrap();
+        let acked = ibc_packet_ack(from.0.as_mut(), mock_env(), ack).unwrap();
+
+        (received, acked)
+    }
+
+    fn bank_send(to: &str, amount: Coin) -> CosmosMsg {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![amount],
+        })
+    }
+
+    #[test]
+    fn channel_handshake() {
+        let mut deps = mock_dependencies();
+
+        let open = mock_ibc_channel_open_try("channel-1", IbcOrder::Ordered, IBC_VERSION);
+        match ibc_channel_open(deps.as_mut(), mock_env(), open) {
+            Err(ContractError::OnlyUnorderedChannel {}) => {}
+            _ => panic!("OnlyUnorderedChannel error should occur"),
+        }
+
+        let open = mock_ibc_channel_open_try("channel-1", IbcOrder::Unordered, "rps-0");
+        match ibc_channel_open(deps.as_mut(), mock_env(), open) {
+            Err(ContractError::InvalidIbcVersion { .. }) => {}
+            _ => panic!("InvalidIbcVersion error should occur"),
+        }
+
+        // packets on unknown channels are rejected with an error ack
+        let packet = RpsPacket::ClaimTimeout { game_id: 1 };
+        let recv = mock_ibc_packet_recv("channel-1", &packet).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), recv).unwrap();
+        match from_binary(&res.acknowledgement).unwrap() {
+            RpsAck::Error(_) => {}
+            _ => panic!("error ack should be returned"),
+        }
+    }
+
+    #[test]
+    fn cross_chain_game() {
+        let mut chain_a = setup("channel-a");
+        let mut chain_b = setup("channel-b");
+
+        // hosty on chain a challenges toasty on chain b, both stake 10
+        let msg = ExecuteMsg::IbcChallenge {
+            channel_id: "channel-a".to_string(),
+            opponent: "toasty".to_string(),
+            move_commitment: commit_move(GameMove::Rock, "long_enough_secret_salt"),
+            host_payout: Some("hosty_b".to_string()),
+            opponent_stake: Some(coin(10, "btoken")),
+        };
+        let info = mock_info("hosty", &coins(10, "atoken"));
+        let res = execute(chain_a.as_mut(), mock_env(), info, msg).unwrap();
+
+        let challenge = sent_packet(&res);
+        relay(
+            &challenge,
+            (&mut chain_a, "channel-a"),
+            (&mut chain_b, "channel-b"),
+            mock_env(),
+        );
+
+        // respond - fail because the stake does not match
+        let msg = ExecuteMsg::IbcRespond {
+            channel_id: "channel-b".to_string(),
+            game_id: 1,
+            opp_move: GameMove::Paper,
+            opponent_payout: Some("toasty_a".to_string()),
+        };
+        let info = mock_info("toasty", &coins(5, "btoken"));
+        let res = execute(chain_b.as_mut(), mock_env(), info, msg.clone());
+
+        match res {
+            Err(ContractError::InvalidWager {}) => {}
+            _ => panic!("InvalidWager error should occur"),
+        }
+
+        // toasty escrows 10 on chain b and answers with paper
+        let info = mock_info("toasty", &coins(10, "btoken"));
+        let res = execute(chain_b.as_mut(), mock_env(), info, msg).unwrap();
+
+        let opp_move = sent_packet(&res);
+        relay(
+            &opp_move,
+            (&mut chain_b, "channel-b"),
+            (&mut chain_a, "channel-a"),
+            mock_env(),
+        );
+
+        // hosty reveals rock on chain a, which pays its stake to toasty's chain a address
+        let msg = ExecuteMsg::IbcRevealMove {
+            channel_id: "channel-a".to_string(),
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "long_enough_secret_salt".to_string(),
+        };
+        let info = mock_info("hosty", &[]);
+        let res = execute(chain_a.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(
+            res.messages[0].msg,
+            bank_send("toasty_a", coin(10, "atoken"))
+        );
+        let resolved = GameResolvedEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(GameKind::Ibc, resolved.kind);
+        assert_eq!(Some(1), resolved.game_id);
+        assert_eq!(GameResult::OpponentWins, resolved.result);
+        assert_eq!(Some(coin(10, "atoken")), resolved.stake);
+
+        // chain b checks the reveal and returns toasty's own stake
+        let result = sent_packet(&res);
+        let (received, _) = relay(
+            &result,
+            (&mut chain_a, "channel-a"),
+            (&mut chain_b, "channel-b"),
+            mock_env(),
+        );
+
+        assert_eq!(
+            received.messages[0].msg,
+            bank_send("toasty", coin(10, "btoken"))
+        );
+        let resolved = GameResolvedEvent::parse(&received.events[0]).unwrap();
+        assert_eq!(GameResult::OpponentWins, resolved.result);
+        assert_eq!(Some(coin(10, "btoken")), resolved.stake);
+        assert!(IBC_GAMES
+            .may_load(&chain_b.storage, ("channel-b", 1))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn cross_chain_timeouts() {
+        let mut chain_a = setup("channel-a");
+        let mut chain_b = setup("channel-b");
+
+        let challenge = |chain: &mut Deployment| {
+            let msg = ExecuteMsg::IbcChallenge {
+                channel_id: "channel-a".to_string(),
+                opponent: "toasty".to_string(),
+                move_commitment: commit_move(GameMove::Rock, "long_enough_secret_salt"),
+                host_payout: Some("hosty_b".to_string()),
+                opponent_stake: Some(coin(10, "btoken")),
+            };
+            let info = mock_info("hosty", &coins(10, "atoken"));
+            sent_packet(&execute(chain.as_mut(), mock_env(), info, msg).unwrap())
+        };
+
+        // a challenge that times out refunds the host
+        let packet = challenge(&mut chain_a);
+        let timeout = mock_ibc_packet_timeout("channel-a", &packet).unwrap();
+        let res = ibc_packet_timeout(chain_a.as_mut(), mock_env(), timeout).unwrap();
+
+        assert_eq!(res.messages[0].msg, bank_send("hosty", coin(10, "atoken")));
+        let cancelled = GameCancelledEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(GameKind::Ibc, cancelled.kind);
+        assert_eq!(Some(1), cancelled.game_id);
+
+        // game 2 is answered but hosty never reveals
+        let packet = challenge(&mut chain_a);
+        relay(
+            &packet,
+            (&mut chain_a, "channel-a"),
+            (&mut chain_b, "channel-b"),
+            mock_env(),
+        );
+
+        let msg = ExecuteMsg::IbcRespond {
+            channel_id: "channel-b".to_string(),
+            game_id: 2,
+            opp_move: GameMove::Scissors,
+            opponent_payout: Some("toasty_a".to_string()),
+        };
+        let info = mock_info("toasty", &coins(10, "btoken"));
+        let res = execute(chain_b.as_mut(), mock_env(), info, msg).unwrap();
+        relay(
+            &sent_packet(&res),
+            (&mut chain_b, "channel-b"),
+            (&mut chain_a, "channel-a"),
+            mock_env(),
+        );
+
+        let msg = ExecuteMsg::IbcClaimTimeout {
+            channel_id: "channel-b".to_string(),
+            game_id: 2,
+        };
+        let info = mock_info("toasty", &[]);
+        let res = execute(chain_b.as_mut(), mock_env(), info, msg).unwrap();
+        let claim = sent_packet(&res);
+
+        // the claim is rejected while the host chain's reveal window is open
+        let (received, acked) = relay(
+            &claim,
+            (&mut chain_b, "channel-b"),
+            (&mut chain_a, "channel-a"),
+            mock_env(),
+        );
+        match from_binary(&received.acknowledgement).unwrap() {
+            RpsAck::Error(_) => {}
+            _ => panic!("error ack should be returned"),
+        }
+        assert!(acked.messages.is_empty());
+
+        // after the window both chains pay toasty
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60 * 60);
+        let (received, acked) = relay(
+            &claim,
+            (&mut chain_b, "channel-b"),
+            (&mut chain_a, "channel-a"),
+            env,
+        );
+
+        assert_eq!(
+            received.messages[0].msg,
+            bank_send("toasty_a", coin(10, "atoken"))
+        );
+        assert_eq!(
+            acked.messages[0].msg,
+            bank_send("toasty", coin(10, "btoken"))
+        );
+
+        // game 3 is never answered
+        let packet = challenge(&mut chain_a);
+        relay(
+            &packet,
+            (&mut chain_a, "channel-a"),
+            (&mut chain_b, "channel-b"),
+            mock_env(),
+        );
+
+        // cancel - fail because toasty's move could still arrive
+        let msg = ExecuteMsg::IbcCancelChallenge {
+            channel_id: "channel-a".to_string(),
+            game_id: 3,
+        };
+        let res = execute(
+            chain_a.as_mut(),
+            mock_env(),
+            mock_info("hosty", &[]),
+            msg.clone(),
+        );
+
+        match res {
+            Err(ContractError::ChallengeNotExpired {}) => {}
+            _ => panic!("ChallengeNotExpired error should occur"),
+        }
+
+        // respond - fail because the challenge expired
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60 * 60 + 1);
+        let respond = ExecuteMsg::IbcRespond {
+            channel_id: "channel-b".to_string(),
+            game_id: 3,
+            opp_move: GameMove::Paper,
+            opponent_payout: Some("toasty_a".to_string()),
+        };
+        let info = mock_info("toasty", &coins(10, "btoken"));
+        let res = execute(chain_b.as_mut(), env.clone(), info, respond);
+
+        match res {
+            Err(ContractError::ChallengeExpired {}) => {}
+            _ => panic!("ChallengeExpired error should occur"),
+        }
+
+        // once a late move would have timed out hosty gets the stake back
+        env.block.time = env.block.time.plus_seconds(60 * 60);
+        let res = execute(chain_a.as_mut(), env, mock_info("hosty", &[]), msg).unwrap();
+        assert_eq!(res.messages[0].msg, bank_send("hosty", coin(10, "atoken")));
+    }
+
+    #[test]
+    fn channel_close() {
+        let mut chain_a = setup("channel-a");
+        let mut chain_b = setup("channel-b");
+
+        // hosty challenges toasty and toasty answers, so both chains hold a stake
+        let msg = ExecuteMsg::IbcChallenge {
+            channel_id: "channel-a".to_string(),
+            opponent: "toasty".to_string(),
+            move_commitment: commit_move(GameMove::Rock, "long_enough_secret_salt"),
+            host_payout: Some("hosty_b".to_string()),
+            opponent_stake: Some(coin(10, "btoken")),
+        };
+        let info = mock_info("hosty", &coins(10, "atoken"));
+        let res = execute(chain_a.as_mut(), mock_env(), info, msg).unwrap();
+        relay(
+            &sent_packet(&res),
+            (&mut chain_a, "channel-a"),
+            (&mut chain_b, "channel-b"),
+            mock_env(),
+        );
+
+        let msg = ExecuteMsg::IbcRespond {
+            channel_id: "channel-b".to_string(),
+            game_id: 1,
+            opp_move: GameMove::Paper,
+            opponent_payout: Some("toasty_a".to_string()),
+        };
+        let info = mock_info("toasty", &coins(10, "btoken"));
+        execute(chain_b.as_mut(), mock_env(), info, msg).unwrap();
+
+        // closing the channel refunds each player on its own chain
+        let close = mock_ibc_channel_close_init("channel-a", IbcOrder::Unordered, IBC_VERSION);
+        let res = ibc_channel_close(chain_a.as_mut(), mock_env(), close).unwrap();
+
+        assert_eq!(res.messages[0].msg, bank_send("hosty", coin(10, "atoken")));
+        let cancelled = GameCancelledEvent::parse(&res.events[0]).unwrap();
+        assert_eq!(Some(1), cancelled.game_id);
+        assert!(query_ibc_channels(chain_a.as_ref()).unwrap().is_empty());
+
+        let close = mock_ibc_channel_close_confirm("channel-b", IbcOrder::Unordered, IBC_VERSION);
+        let res = ibc_channel_close(chain_b.as_mut(), mock_env(), close).unwrap();
+
+        assert_eq!(res.messages[0].msg, bank_send("toasty", coin(10, "btoken")));
+        assert!(IBC_GAMES
+            .may_load(&chain_b.storage, ("channel-b", 1))
+            .unwrap()
+            .is_none());
+    }
+}
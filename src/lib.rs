@@ -1,6 +1,10 @@
 pub mod contract;
+mod drand;
 mod error;
+pub mod events;
+pub mod ibc;
 pub mod msg;
+mod relay;
 pub mod state;
 
 pub use crate::error::ContractError;
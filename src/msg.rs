@@ -1,8 +1,14 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Coin, CosmosMsg, StdResult, Timestamp, Uint128, WasmMsg,
+};
+use cw0::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::{Game, GameMove};
+use crate::state::{
+    EmissionRate, Game, GameCallback, GameMove, GameResult, Guild, Hill, HillConfig, HouseConfig,
+    OperatorGrant, QueueEntry, RankedConfig, StreakMilestone,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -13,8 +19,15 @@ pub struct InstantiateMsg {
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     StartGame {
+        // an address or "@nickname", see SetProfile
         opponent: Addr,
         host_move: GameMove,
+        callback: Option<GameCallback>,
+        // set by an operator to the player it starts the game for
+        host: Option<Addr>,
+    },
+    CancelGame {
+        opponent: Addr,
     },
     UpdateAdmin {
         admin: Addr,
@@ -30,6 +43,305 @@ pub enum ExecuteMsg {
         opponent: Addr,
         opp_move: GameMove,
     },
+    // move_commitment is the sha256 hash of the lowercase move name followed by a
+    // secret salt of at least 16 bytes, e.g. sha256("rock" + salt). See
+    // contract::commit_move. Players are paired with the oldest entry of the
+    // same denom whose wager has as many digits, and both play for the smaller
+    // wager. There are no player ratings, so pairing never looks at skill.
+    JoinQueue {
+        wager: Coin,
+        move_commitment: Binary,
+        player: Option<Addr>,
+    },
+    LeaveQueue {
+        player: Option<Addr>,
+    },
+    // anyone can refund the oldest expired queue entries
+    PurgeQueue {
+        limit: Option<u32>,
+    },
+    RevealMove {
+        game_id: u64,
+        player_move: GameMove,
+        salt: String,
+        player: Option<Addr>,
+    },
+    ClaimTimeout {
+        game_id: u64,
+    },
+    // Spectators bet on queued games until the first move is revealed. Only
+    // queued games take side bets: direct games, hill challenges and house
+    // games store a move in the clear, and a round has no host or opponent
+    // to back. Any other game id fails with NoGameFound.
+    PlaceSideBet {
+        game_id: u64,
+        outcome: GameResult,
+    },
+    // any address can attach a single coin to a pending game or round, the
+    // bonus goes to the winner and is split on ties. A game takes up to ten
+    // sponsors, sponsoring again in the same denom adds to the bonus.
+    SponsorGame {
+        game: SponsoredGame,
+    },
+    // pays out a bet once its pool is settled or void
+    ClaimSideBet {
+        game_id: u64,
+    },
+    UpdateSideBetFee {
+        fee_bps: u16,
+    },
+    UpdateHouseConfig {
+        drand_pubkey: Binary,
+        drand_genesis: u64,
+        drand_period: u64,
+    },
+    FundHouse {},
+    WithdrawHouse {
+        amount: Coin,
+    },
+    PlayHouse {
+        player_move: GameMove,
+        round: u64,
+    },
+    SubmitBeacon {
+        game_id: u64,
+        previous_signature: Binary,
+        signature: Binary,
+    },
+    // a house game whose beacon was not submitted in time is forfeited to the
+    // house, anyone can send it
+    ClaimHouseForfeit {
+        game_id: u64,
+    },
+    // game_id refers to a finished game record. The accepted rematch is played
+    // as a queued game with the roles swapped, revealed with RevealMove.
+    Rematch {
+        game_id: u64,
+        move_commitment: Binary,
+        player: Option<Addr>,
+    },
+    AcceptRematch {
+        game_id: u64,
+        move_commitment: Binary,
+        player: Option<Addr>,
+    },
+    CancelRematch {
+        game_id: u64,
+        player: Option<Addr>,
+    },
+    // the stake, if any, is paid with the first commitment of every player
+    CreateRound {
+        players: Vec<Addr>,
+        stake: Option<Coin>,
+    },
+    CommitRoundMove {
+        round_id: u64,
+        move_commitment: Binary,
+        player: Option<Addr>,
+    },
+    RevealRoundMove {
+        round_id: u64,
+        player_move: GameMove,
+        salt: String,
+        player: Option<Addr>,
+    },
+    ClaimRoundTimeout {
+        round_id: u64,
+    },
+    UpdateHillConfig {
+        challenge_fee: Coin,
+        defenses_to_cash_out: u32,
+        cash_out_after: u64,
+    },
+    ClaimHill {
+        move_commitment: Binary,
+        player: Option<Addr>,
+    },
+    // commits the first defense of a champion crowned by a challenge, the move
+    // stays locked until it is revealed in DefendHill
+    CommitHillMove {
+        move_commitment: Binary,
+        player: Option<Addr>,
+    },
+    ChallengeHill {
+        challenger_move: GameMove,
+        player: Option<Addr>,
+    },
+    // reveals the committed defense and commits the move for the next defense
+    DefendHill {
+        player_move: GameMove,
+        salt: String,
+        next_commitment: Binary,
+        player: Option<Addr>,
+    },
+    ClaimHillTimeout {},
+    CashOutHill {
+        player: Option<Addr>,
+    },
+    AddSubscriber {
+        address: Addr,
+    },
+    RemoveSubscriber {
+        address: Addr,
+    },
+    // with reply_on_error a failing subscriber no longer reverts the game
+    UpdateSubscriberMode {
+        reply_on_error: bool,
+    },
+    // cw721 contract trophies are minted on, this contract must be its minter
+    UpdateTrophyContract {
+        contract: Option<Addr>,
+    },
+    // cw20 contract rewards are minted on, this contract must be its minter.
+    // The schedule must be sorted by start time.
+    UpdateRewardConfig {
+        token: Addr,
+        daily_cap: Uint128,
+        schedule: Vec<EmissionRate>,
+    },
+    UpdateStreakConfig {
+        ties_break_streak: bool,
+        milestones: Vec<StreakMilestone>,
+    },
+    FundTreasury {},
+    WithdrawTreasury {
+        amount: Coin,
+    },
+    UpdateRankedConfig {
+        config: Option<RankedConfig>,
+    },
+    // replaces the sender's profile, nicknames are unique regardless of case
+    SetProfile {
+        nickname: Option<String>,
+        avatar_uri: Option<String>,
+        bio: Option<String>,
+    },
+    // lets the admin take an offensive nickname away from a player
+    ClearNickname {
+        player: Addr,
+    },
+    CreateGuild {
+        name: String,
+    },
+    // sent by the guild leader
+    InviteToGuild {
+        player: Addr,
+    },
+    AcceptGuildInvite {
+        guild_id: u64,
+    },
+    // a leaving leader hands the guild to the next oldest member
+    LeaveGuild {},
+    // sent by the guild leader, the war starts once the other leader accepts
+    DeclareGuildWar {
+        opponent_guild: u64,
+        duration: u64,
+    },
+    AcceptGuildWar {
+        war_id: u64,
+    },
+    // anyone can settle a war that has ended
+    FinishGuildWar {
+        war_id: u64,
+    },
+    StartSeason {},
+    // prizes[0] goes to the best player of the season, prizes[1] to the
+    // second and so on. The funds sent along must cover them, and only the
+    // archived top players can win one.
+    EndSeason {
+        prizes: Vec<Coin>,
+    },
+    // The operator can then play in the player's name, by setting host in
+    // StartGame or player in the queue, rematch, round and hill messages. Funds
+    // it attaches there are capped by max_wager, a grant without one can only
+    // play free games.
+    GrantOperator {
+        operator: Addr,
+        expires: Expiration,
+        max_wager: Option<Coin>,
+    },
+    RevokeOperator {
+        operator: Addr,
+    },
+    // funds sent along are the deposit, the counterparty must match it to join
+    OpenChannel {
+        counterparty: Addr,
+        pubkey: Binary,
+    },
+    JoinChannel {
+        channel_id: u64,
+        pubkey: Binary,
+    },
+    CancelChannel {
+        channel_id: u64,
+    },
+    // a final state closes the channel, any other starts or answers the challenge period
+    SettleChannel {
+        channel_id: u64,
+        state: SignedChannelState,
+    },
+    DisputeChannelRound {
+        channel_id: u64,
+        move_a: SignedChannelMove,
+        move_b: SignedChannelMove,
+    },
+    // a player who does not reveal before the dispute expires loses the stake
+    RevealChannelMove {
+        channel_id: u64,
+        player_move: GameMove,
+        salt: String,
+    },
+    FinalizeChannel {
+        channel_id: u64,
+    },
+    // challenges a player on the chain at the other end of an ibc channel, the
+    // funds sent along are escrowed here until the host chain decides the game
+    IbcChallenge {
+        channel_id: String,
+        opponent: String,
+        move_commitment: Binary,
+        // the host's address on the opponent's chain, paid if the host wins
+        host_payout: Option<String>,
+        opponent_stake: Option<Coin>,
+    },
+    // refunds a challenge once no move can reach this chain anymore
+    IbcCancelChallenge {
+        channel_id: String,
+        game_id: u64,
+    },
+    IbcRespond {
+        channel_id: String,
+        game_id: u64,
+        opp_move: GameMove,
+        // the opponent's address on the host chain, paid if the opponent wins
+        opponent_payout: Option<String>,
+    },
+    IbcRevealMove {
+        channel_id: String,
+        game_id: u64,
+        host_move: GameMove,
+        salt: String,
+    },
+    IbcClaimTimeout {
+        channel_id: String,
+        game_id: u64,
+    },
+    // sent by a strategy contract to opt in to (or out of) playing as a bot
+    RegisterBot {},
+    UnregisterBot {},
+    // Plays a game against a registered bot in one transaction: the bot is
+    // queried for its move with a nonce derived from the block and the game is
+    // resolved right away. Bot games are never ranked.
+    PlayBotGame {
+        bot: Addr,
+        host_move: GameMove,
+    },
+    // submitted by a relayer on behalf of the player who signed the payload
+    RelayedMove {
+        signed_payload: Binary,
+        pubkey: Binary,
+        signature: Binary,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -40,9 +352,368 @@ pub enum QueryMsg {
     GetGamesByOpponent { opponent: Addr },
     GetGameByHostAndOpponent { host: Addr, opponent: Addr },
     GetAdmin {},
+    GetState {},
+    GetQueue {},
+    GetQueuedGame { game_id: u64 },
+    GetSidePool { game_id: u64 },
+    GetSideBet { game_id: u64, bettor: Addr },
+    GetHouse {},
+    GetHouseGame { game_id: u64 },
+    GetGameRecord { game_id: u64 },
+    GetRematchOffer { game_id: u64 },
+    GetRound { round_id: u64 },
+    GetHill {},
+    GetSubscribers {},
+    GetRelayNonce { player: Addr },
+    ListOperators { player: Addr },
+    GetChannel { channel_id: u64 },
+    ListIbcChannels {},
+    GetIbcGame { channel_id: String, game_id: u64 },
+    ListBots {},
+    GetRewardConfig {},
+    GetRewardAllowance { player: Addr },
+    GetPlayerStats { player: Addr },
+    GetHeadToHead { a: Addr, b: Addr },
+    GetGlobalStats {},
+    // without a number the current season with its live top standings
+    GetSeason { number: Option<u64> },
+    // the longest running win streaks, longest first
+    GetStreaks { limit: Option<u32> },
+    GetStreakConfig {},
+    GetRankedConfig {},
+    GetProfile { player: Addr },
+    GetGuild { guild_id: u64 },
+    // best guild first
+    GetGuildStandings {},
+    GetGuildWar { war_id: u64 },
+    ResolveNickname { nickname: String },
+    GetTreasury {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SponsoredGame {
+    Direct { host: Addr, opponent: Addr },
+    Queued { game_id: u64 },
+    Round { round_id: u64 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct GamesListResponse {
     pub games: Vec<Game>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueueResponse {
+    pub entries: Vec<QueueEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HouseResponse {
+    pub config: Option<HouseConfig>,
+    pub bankroll: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HillResponse {
+    pub config: Option<HillConfig>,
+    pub hill: Hill,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RelayNonceResponse {
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IbcChannelsResponse {
+    pub channels: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorsResponse {
+    pub operators: Vec<OperatorGrant>,
+}
+
+// Wins are reported in the order the players were asked for
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HeadToHeadResponse {
+    pub a: Addr,
+    pub b: Addr,
+    pub wins_a: u64,
+    pub wins_b: u64,
+    pub ties: u64,
+    pub last_game: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GuildStandingsResponse {
+    pub guilds: Vec<Guild>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResolveNicknameResponse {
+    pub address: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StreaksResponse {
+    pub streaks: Vec<StreakEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StreakEntry {
+    pub player: Addr,
+    pub streak: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardAllowanceResponse {
+    // what the player can still be minted today
+    pub remaining: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BotsResponse {
+    pub bots: Vec<Addr>,
+}
+
+// The payload a player signs for a relayed move. It must be sent in the exact
+// serialization of this struct, the signature covers sha256 of those bytes.
+// The chain and contract pin the payload to this deployment.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RelayedPayload {
+    pub chain_id: String,
+    pub contract: Addr,
+    pub player: Addr,
+    pub nonce: u64,
+    pub action: RelayedAction,
+}
+
+// moves that need no funds from the player can be relayed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RelayedAction {
+    StartGame {
+        opponent: Addr,
+        host_move: GameMove,
+    },
+    OpponentResponse {
+        host: Addr,
+        opp_move: GameMove,
+    },
+    RevealMove {
+        game_id: u64,
+        player_move: GameMove,
+        salt: String,
+    },
+    CommitRoundMove {
+        round_id: u64,
+        move_commitment: Binary,
+    },
+    RevealRoundMove {
+        round_id: u64,
+        player_move: GameMove,
+        salt: String,
+    },
+}
+
+// Tally both players of a channel sign off-chain, the signatures cover sha256
+// of the exact bytes in the canonical serialization of this struct.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChannelState {
+    pub chain_id: String,
+    pub contract: Addr,
+    pub channel_id: u64,
+    pub nonce: u64,
+    pub balance_a: Uint128,
+    pub balance_b: Uint128,
+    pub is_final: bool,
+}
+
+// A single player's committed move for the round after the tally with nonce - 1,
+// see contract::commit_move. Players swap signed commitments before revealing
+// off-chain, so neither can pick a move after seeing the other's. Both
+// commitments of a round open a dispute on-chain when the loser will not sign
+// the tally, the moves are then revealed with RevealChannelMove.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChannelMove {
+    pub chain_id: String,
+    pub contract: Addr,
+    pub channel_id: u64,
+    pub nonce: u64,
+    pub move_commitment: Binary,
+    pub stake: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedChannelState {
+    pub payload: Binary,
+    pub signature_a: Binary,
+    pub signature_b: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedChannelMove {
+    pub payload: Binary,
+    pub signature: Binary,
+}
+
+// Sent to every subscriber contract as `{"game_finished": {...}}` when a
+// two-party game is resolved
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameFinishedHookMsg {
+    pub game_id: u64,
+    pub host: Addr,
+    pub opponent: Addr,
+    pub host_move: Option<GameMove>,
+    pub opp_move: Option<GameMove>,
+    pub result: GameResult,
+    pub stake: Option<Coin>,
+}
+
+impl GameFinishedHookMsg {
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_binary(&GameHookExecuteMsg::GameFinished(self))
+    }
+
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.into_binary()?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GameHookExecuteMsg {
+    GameFinished(GameFinishedHookMsg),
+}
+
+// Sent to the callback contract of a game as `{"rps_callback": {...}}`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameCallbackMsg {
+    pub host: Addr,
+    pub opponent: Addr,
+    pub outcome: GameOutcome,
+    pub callback: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GameOutcome {
+    Resolved { game_id: u64, result: GameResult },
+    Cancelled {},
+}
+
+impl GameCallbackMsg {
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_binary(&GameCallbackExecuteMsg::RpsCallback(self))
+    }
+
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.into_binary()?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GameCallbackExecuteMsg {
+    RpsCallback(GameCallbackMsg),
+}
+
+// Smart query sent to a registered bot as `{"get_move": {...}}` when a game
+// against it is played. The nonce is the sha256 hash of the chain id, block
+// height, block time and game id; the host's move is not part of the query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BotQueryMsg {
+    GetMove {
+        game_id: u64,
+        host: Addr,
+        nonce: Binary,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BotMoveResponse {
+    pub bot_move: GameMove,
+}
+
+// Sent to the trophy contract as `{"mint": {...}}`, mirroring the cw721-base
+// mint message with the trophy metadata as its extension
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TrophyMintMsg {
+    pub token_id: String,
+    pub owner: String,
+    pub token_uri: Option<String>,
+    pub extension: TrophyMetadata,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TrophyMetadata {
+    pub kind: TrophyKind,
+    // the game record id of a win, the round id of a championship
+    pub game_id: u64,
+    pub opponents: Vec<Addr>,
+    pub winner_move: Option<GameMove>,
+    pub opponent_move: Option<GameMove>,
+    pub won_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrophyKind {
+    Win,
+    // won a multi-player round
+    Championship,
+    // reached a streak milestone with the game
+    StreakMilestone { streak: u64 },
+}
+
+impl TrophyMintMsg {
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_binary(&Cw721ExecuteMsg::Mint(self))
+    }
+
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.into_binary()?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw721ExecuteMsg {
+    Mint(TrophyMintMsg),
+}
+
+// Sent to the reward token as `{"mint": {...}}`, mirroring the cw20 mint message
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20ExecuteMsg {
+    Mint { recipient: String, amount: Uint128 },
+}
+
+impl Cw20ExecuteMsg {
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: to_binary(&self)?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
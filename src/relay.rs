@@ -0,0 +1,159 @@
+use cosmwasm_std::CanonicalAddr;
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256};
+
+// relayed payloads are signed over sha256 of their exact bytes
+pub fn payload_hash(payload: &[u8]) -> [u8; 32] {
+    Sha256::digest(payload).into()
+}
+
+// cosmos accounts are ripemd160(sha256(pubkey)) of the compressed secp256k1 key
+pub fn pubkey_to_canonical(pubkey: &[u8]) -> Option<CanonicalAddr> {
+    if pubkey.len() != 33 {
+        return None;
+    }
+
+    let hash = Ripemd160::digest(&Sha256::digest(pubkey));
+    Some(CanonicalAddr::from(hash.as_slice()))
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::marker::PhantomData;
+
+    use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{
+        Addr, Api, CanonicalAddr, OwnedDeps, RecoverPubkeyError, StdResult, VerificationError,
+    };
+    use k256::ecdsa::signature::DigestSigner;
+    use k256::ecdsa::{Signature, SigningKey};
+    use sha2::{Digest, Sha256};
+
+    use super::pubkey_to_canonical;
+
+    // fixed keys standing in for the players' wallets when signing test payloads
+    pub const PLAYER_KEY: u8 = 0x42;
+    pub const OTHER_KEY: u8 = 0x43;
+
+    pub fn pubkey(key: u8) -> Vec<u8> {
+        let key = SigningKey::from_bytes(&[key; 32]).unwrap();
+        key.verifying_key().to_bytes().to_vec()
+    }
+
+    pub fn sign(key: u8, payload: &[u8]) -> Vec<u8> {
+        let key = SigningKey::from_bytes(&[key; 32]).unwrap();
+        let signature: Signature = key.sign_digest(Sha256::new().chain(payload));
+        signature.as_ref().to_vec()
+    }
+
+    // the address the chain derives for a test key
+    pub fn player(key: u8) -> Addr {
+        RelayApi::default()
+            .addr_humanize(&pubkey_to_canonical(&pubkey(key)).unwrap())
+            .unwrap()
+    }
+
+    pub fn relay_dependencies() -> OwnedDeps<MockStorage, RelayApi, MockQuerier> {
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: RelayApi::default(),
+            querier: MockQuerier::default(),
+            custom_query_type: PhantomData,
+        }
+    }
+
+    // the mock api cannot humanize 20 byte account hashes, so they are hex encoded instead
+    #[derive(Default)]
+    pub struct RelayApi(MockApi);
+
+    impl Api for RelayApi {
+        fn addr_validate(&self, human: &str) -> StdResult<Addr> {
+            self.0.addr_validate(human)
+        }
+
+        fn addr_canonicalize(&self, human: &str) -> StdResult<CanonicalAddr> {
+            self.0.addr_canonicalize(human)
+        }
+
+        fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+            if canonical.len() != 20 {
+                return self.0.addr_humanize(canonical);
+            }
+
+            let hex: String = canonical
+                .as_slice()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            Ok(Addr::unchecked(format!("terra{}", hex)))
+        }
+
+        fn secp256k1_verify(
+            &self,
+            message_hash: &[u8],
+            signature: &[u8],
+            public_key: &[u8],
+        ) -> Result<bool, VerificationError> {
+            self.0.secp256k1_verify(message_hash, signature, public_key)
+        }
+
+        fn secp256k1_recover_pubkey(
+            &self,
+            message_hash: &[u8],
+            signature: &[u8],
+            recovery_param: u8,
+        ) -> Result<Vec<u8>, RecoverPubkeyError> {
+            self.0
+                .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+        }
+
+        fn ed25519_verify(
+            &self,
+            message: &[u8],
+            signature: &[u8],
+            public_key: &[u8],
+        ) -> Result<bool, VerificationError> {
+            self.0.ed25519_verify(message, signature, public_key)
+        }
+
+        fn ed25519_batch_verify(
+            &self,
+            messages: &[&[u8]],
+            signatures: &[&[u8]],
+            public_keys: &[&[u8]],
+        ) -> Result<bool, VerificationError> {
+            self.0
+                .ed25519_batch_verify(messages, signatures, public_keys)
+        }
+
+        fn debug(&self, message: &str) {
+            self.0.debug(message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::{pubkey, sign, PLAYER_KEY};
+    use super::*;
+    use cosmwasm_std::testing::MockApi;
+    use cosmwasm_std::Api;
+
+    #[test]
+    fn derive_and_verify() {
+        let canonical = pubkey_to_canonical(&pubkey(PLAYER_KEY)).unwrap();
+        assert_eq!(20, canonical.len());
+
+        // uncompressed or truncated keys are rejected
+        assert_eq!(None, pubkey_to_canonical(&pubkey(PLAYER_KEY)[..32]));
+
+        let api = MockApi::default();
+        let signature = sign(PLAYER_KEY, b"payload");
+        assert!(api
+            .secp256k1_verify(&payload_hash(b"payload"), &signature, &pubkey(PLAYER_KEY))
+            .unwrap());
+        assert!(!api
+            .secp256k1_verify(&payload_hash(b"tampered"), &signature, &pubkey(PLAYER_KEY))
+            .unwrap());
+    }
+}
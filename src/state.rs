@@ -1,7 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Binary, Coin, Empty, IbcEndpoint, Timestamp, Uint128};
+use cw0::Expiration;
 use cw_controllers::{Admin, Hooks};
 use cw_storage_plus::{Item, Map};
 
@@ -9,12 +10,96 @@ use cw_storage_plus::{Item, Map};
 pub struct State {
     pub owner: Addr,
     pub admin: Addr,
+    // cw721 contract trophies are minted on, see msg::TrophyMintMsg
+    #[serde(default)]
+    pub trophy_contract: Option<Addr>,
 }
 
 pub const ADMIN: Admin = Admin::new("admin");
 pub const STATE: Item<State> = Item::new("state");
 pub const GAME: Map<(&Addr, &Addr), Game> = Map::new("game");
 pub const HOOKS: Hooks = Hooks::new("hooks");
+pub const QUEUE: Map<u64, QueueEntry> = Map::new("queue");
+pub const QUEUE_SEQ: Item<u64> = Item::new("queue_seq");
+// queue entry ids keyed by the wager bracket they wait in, see contract::queue_bracket
+pub const QUEUE_BY_WAGER: Map<(&str, u64), Empty> = Map::new("queue_by_wager");
+// the queue entry id of each waiting player
+pub const QUEUED_PLAYERS: Map<&Addr, u64> = Map::new("queued_players");
+pub const QUEUED_GAMES: Map<u64, QueuedGame> = Map::new("queued_games");
+pub const QUEUED_GAME_SEQ: Item<u64> = Item::new("queued_game_seq");
+pub const HOUSE: Item<HouseConfig> = Item::new("house");
+pub const HOUSE_BANKROLL: Map<&str, Uint128> = Map::new("house_bankroll");
+pub const HOUSE_GAMES: Map<u64, HouseGame> = Map::new("house_games");
+pub const HOUSE_GAME_SEQ: Item<u64> = Item::new("house_game_seq");
+pub const GAME_RECORDS: Map<u64, GameRecord> = Map::new("game_records");
+pub const GAME_RECORD_SEQ: Item<u64> = Item::new("game_record_seq");
+pub const REMATCH_OFFERS: Map<u64, RematchOffer> = Map::new("rematch_offers");
+pub const ROUNDS: Map<u64, Round> = Map::new("rounds");
+pub const ROUND_SEQ: Item<u64> = Item::new("round_seq");
+pub const HILL_CONFIG: Item<HillConfig> = Item::new("hill_config");
+pub const HILL: Item<Hill> = Item::new("hill");
+// contracts notified about every finished game, unlike HOOKS which holds the blacklist
+pub const SUBSCRIBERS: Hooks = Hooks::new("subscribers");
+pub const SUBSCRIBER_REPLY_ON_ERROR: Item<bool> = Item::new("subscriber_reply_on_error");
+// next nonce expected in a relayed move of each player
+pub const RELAY_NONCES: Map<&Addr, u64> = Map::new("relay_nonces");
+// operators keyed by (player, operator)
+pub const OPERATORS: Map<(&Addr, &Addr), OperatorGrant> = Map::new("operators");
+pub const CHANNELS: Map<u64, Channel> = Map::new("channels");
+pub const CHANNEL_SEQ: Item<u64> = Item::new("channel_seq");
+// connected ibc channels and the endpoint on the other chain
+pub const IBC_CHANNELS: Map<&str, IbcEndpoint> = Map::new("ibc_channels");
+// cross-chain games keyed by (local channel id, game id on the host chain)
+pub const IBC_GAMES: Map<(&str, u64), IbcGame> = Map::new("ibc_games");
+pub const IBC_GAME_SEQ: Item<u64> = Item::new("ibc_game_seq");
+// strategy contracts that opted in to answer their games through a query
+pub const BOTS: Map<&Addr, Empty> = Map::new("bots");
+pub const BOT_GAME_SEQ: Item<u64> = Item::new("bot_game_seq");
+pub const REWARD_CONFIG: Item<RewardConfig> = Item::new("reward_config");
+// rewards minted to each player on the current day
+pub const DAILY_REWARDS: Map<&Addr, DailyRewards> = Map::new("daily_rewards");
+pub const PLAYER_STATS: Map<&Addr, PlayerStats> = Map::new("player_stats");
+// record between two players keyed by the pair in ascending address order
+pub const HEAD_TO_HEAD: Map<(&Addr, &Addr), HeadToHead> = Map::new("head_to_head");
+pub const GLOBAL_STATS: Item<GlobalStats> = Item::new("global_stats");
+// the season being played, its live standings are in SEASON_SCORES
+pub const CURRENT_SEASON: Item<Season> = Item::new("current_season");
+// scores are keyed by season number, so a new season starts from scratch
+pub const SEASON_SCORES: Map<(u64, &Addr), SeasonScore> = Map::new("season_scores");
+// (season, rank, player) where the rank is the big endian wins followed by
+// u64::MAX - losses, so ranging descending lists the best players first
+pub const SEASON_RANKS: Map<(u64, Vec<u8>, &Addr), Empty> = Map::new("season_ranks");
+// ended seasons with their final standings
+pub const SEASONS: Map<u64, Season> = Map::new("seasons");
+pub const SEASON_SEQ: Item<u64> = Item::new("season_seq");
+pub const STREAK_CONFIG: Item<StreakConfig> = Item::new("streak_config");
+// players with a running win streak keyed by (streak, player), for the top streaks
+pub const STREAKS: Map<(u64, &Addr), Empty> = Map::new("streaks");
+// funds by denom that streak bonuses are paid from
+pub const TREASURY: Map<&str, Uint128> = Map::new("treasury");
+// side bets on queued games keyed by the queued game id
+pub const SIDE_POOLS: Map<u64, SidePool> = Map::new("side_pools");
+pub const SIDE_BETS: Map<(u64, &Addr), SideStakes> = Map::new("side_bets");
+// fee on settled side pools in basis points, paid into the treasury
+pub const SIDE_BET_FEE_BPS: Item<u16> = Item::new("side_bet_fee_bps");
+pub const RANKED_CONFIG: Item<RankedConfig> = Item::new("ranked_config");
+pub const PROFILES: Map<&Addr, Profile> = Map::new("profiles");
+// owner of each nickname keyed by its lowercase form
+pub const NICKNAMES: Map<&str, Addr> = Map::new("nicknames");
+pub const GUILDS: Map<u64, Guild> = Map::new("guilds");
+pub const GUILD_SEQ: Item<u64> = Item::new("guild_seq");
+// id of each guild keyed by its lowercase name
+pub const GUILD_NAMES: Map<&str, u64> = Map::new("guild_names");
+// the guild each player is a member of
+pub const GUILD_MEMBERS: Map<&Addr, u64> = Map::new("guild_members");
+// open invites keyed by (guild id, player)
+pub const GUILD_INVITES: Map<(u64, &Addr), Empty> = Map::new("guild_invites");
+pub const GUILD_WARS: Map<u64, GuildWar> = Map::new("guild_wars");
+pub const GUILD_WAR_SEQ: Item<u64> = Item::new("guild_war_seq");
+// the running war between two guilds keyed by the lower guild id first
+pub const ACTIVE_GUILD_WARS: Map<(u64, u64), u64> = Map::new("active_guild_wars");
+// ranked activity between two players keyed by the pair in ascending address order
+pub const PAIR_ACTIVITY: Map<(&Addr, &Addr), PairActivity> = Map::new("pair_activity");
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Game {
@@ -23,6 +108,24 @@ pub struct Game {
     pub host_move: GameMove,
     pub opp_move: Option<GameMove>,
     pub result: Option<GameResult>,
+    pub callback: Option<GameCallback>,
+    #[serde(default)]
+    pub sponsors: Vec<Sponsorship>,
+}
+
+// A bonus attached to a pending game or round by a third party. It goes to the
+// winner, is split on ties and refunded when the game is cancelled.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Sponsorship {
+    pub sponsor: Addr,
+    pub bonus: Coin,
+}
+
+// Executed on `contract` once the game is resolved or cancelled, see msg::GameCallbackMsg
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameCallback {
+    pub contract: Addr,
+    pub msg: Binary,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Copy)]
@@ -32,9 +135,525 @@ pub enum GameMove {
     Scissors,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Copy)]
 pub enum GameResult {
     HostWins,
     OpponentWins,
     Tie,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueueEntry {
+    pub id: u64,
+    pub player: Addr,
+    pub wager: Coin,
+    pub move_commitment: Binary,
+    pub expires: Expiration,
+}
+
+// A game created by the matchmaking queue. Both moves are committed up front
+// and revealed afterwards, so neither player can react to the other's move.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueuedGame {
+    pub id: u64,
+    pub host: Addr,
+    pub opponent: Addr,
+    pub wager: Option<Coin>,
+    pub host_commitment: Binary,
+    pub opp_commitment: Binary,
+    pub host_move: Option<GameMove>,
+    pub opp_move: Option<GameMove>,
+    pub reveal_expires: Expiration,
+    #[serde(default)]
+    pub sponsors: Vec<Sponsorship>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HouseConfig {
+    pub drand_pubkey: Binary,
+    pub drand_genesis: u64,
+    pub drand_period: u64,
+}
+
+// A game against the house. The house move is derived from the drand beacon of
+// `round`, which must still be in the future when the game is created.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HouseGame {
+    pub id: u64,
+    pub player: Addr,
+    pub player_move: GameMove,
+    pub wager: Coin,
+    pub round: u64,
+    // once expired without a beacon the house can claim the pot
+    #[serde(default)]
+    pub beacon_expires: Expiration,
+}
+
+// A finished two-party game, kept so players can look it up and ask for a rematch.
+// Moves are missing when a player never revealed before the timeout.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameRecord {
+    pub id: u64,
+    pub host: Addr,
+    pub opponent: Addr,
+    pub host_move: Option<GameMove>,
+    pub opp_move: Option<GameMove>,
+    pub result: GameResult,
+    pub wager: Option<Coin>,
+    pub finished_at: Timestamp,
+    // counted toward the standings and rewards, see RankedConfig
+    #[serde(default)]
+    pub ranked: bool,
+    // the bonuses paid out with the game
+    #[serde(default)]
+    pub sponsors: Vec<Sponsorship>,
+    // the queued game of the accepted rematch, a record is only rematched once
+    #[serde(default)]
+    pub rematch_id: Option<u64>,
+    // played against a registered bot, never ranked
+    #[serde(default)]
+    pub bot: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RematchOffer {
+    pub game_id: u64,
+    pub proposer: Addr,
+    pub move_commitment: Binary,
+}
+
+// A game between more than two players. Every remaining player commits and then
+// reveals a move, losers are eliminated until a single winner takes the pot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Round {
+    pub id: u64,
+    pub creator: Addr,
+    pub stake: Option<Coin>,
+    pub pot: Uint128,
+    pub players: Vec<RoundPlayer>,
+    pub round_number: u32,
+    pub phase: RoundPhase,
+    pub phase_expires: Expiration,
+    pub winner: Option<Addr>,
+    #[serde(default)]
+    pub sponsors: Vec<Sponsorship>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundPlayer {
+    pub address: Addr,
+    pub paid: bool,
+    pub eliminated: bool,
+    pub commitment: Option<Binary>,
+    pub revealed: Option<GameMove>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundPhase {
+    Commit,
+    Reveal,
+    Finished,
+}
+
+// Outcome of one round: with exactly two distinct moves the players of the
+// winning move stay in, with one or all three moves the round is replayed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum RoundResult {
+    Eliminate { winning_move: GameMove },
+    Replay,
+}
+
+// Counters of a player's resolved two-party games, kept up to date in
+// contract::finish_game. Streaks count consecutive wins of ranked games
+// with a stake.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PlayerStats {
+    pub games_played: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub ties: u64,
+    pub current_streak: u64,
+    pub best_streak: u64,
+    pub total_wagered: Vec<Coin>,
+    // the pots paid out for won games
+    pub total_won: Vec<Coin>,
+    pub moves: MoveCounts,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Profile {
+    pub nickname: Option<String>,
+    pub avatar_uri: Option<String>,
+    pub bio: Option<String>,
+}
+
+// The side bets of a queued game. The fee is fixed by the first bet, and the
+// pool is settled with the result once both players revealed, or voided when
+// the game times out or nobody can be paid.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SidePool {
+    pub game_id: u64,
+    pub denom: String,
+    pub fee_bps: u16,
+    pub stakes: SideStakes,
+    pub status: SidePoolStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SidePoolStatus {
+    Open,
+    Settled {
+        result: GameResult,
+        fee: Uint128,
+        // winning stakes claimed so far and what was paid out on them, the
+        // rounding left once every winner claimed goes to the treasury
+        #[serde(default)]
+        claimed: Uint128,
+        #[serde(default)]
+        paid: Uint128,
+    },
+    // every bettor gets their stakes back
+    Void,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct SideStakes {
+    pub host_wins: Uint128,
+    pub opponent_wins: Uint128,
+    pub tie: Uint128,
+}
+
+impl SideStakes {
+    pub fn on(&self, outcome: GameResult) -> Uint128 {
+        match outcome {
+            GameResult::HostWins => self.host_wins,
+            GameResult::OpponentWins => self.opponent_wins,
+            GameResult::Tie => self.tie,
+        }
+    }
+
+    pub fn add(&mut self, outcome: GameResult, amount: Uint128) {
+        match outcome {
+            GameResult::HostWins => self.host_wins += amount,
+            GameResult::OpponentWins => self.opponent_wins += amount,
+            GameResult::Tie => self.tie += amount,
+        }
+    }
+
+    pub fn total(&self) -> Uint128 {
+        self.host_wins + self.opponent_wins + self.tie
+    }
+}
+
+// Members are kept in joining order, the first one is the leader. The score
+// counts the games members played against members of other guilds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Guild {
+    pub id: u64,
+    pub name: String,
+    pub leader: Addr,
+    pub members: Vec<Addr>,
+    pub score: SeasonScore,
+}
+
+// Declared by the leader of guild_a and accepted by the leader of guild_b.
+// The guild whose members win more games against the other's members before
+// the war ends wins it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GuildWar {
+    pub id: u64,
+    pub guild_a: u64,
+    pub guild_b: u64,
+    // length in seconds, counted from the acceptance
+    pub duration: u64,
+    pub ends: Option<Timestamp>,
+    pub wins_a: u64,
+    pub wins_b: u64,
+    pub status: GuildWarStatus,
+    pub winner: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GuildWarStatus {
+    Declared,
+    Active,
+    Finished,
+}
+
+// Limits on what counts as a ranked game. Games past a limit are still played,
+// they just do not count toward the season and guild standings, nor earn
+// rewards or streak bonuses. Without a config every game between two
+// different players is ranked.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RankedConfig {
+    // ranked games a pair can play per window
+    pub max_pair_games: u64,
+    // window length in seconds
+    pub window: u64,
+    pub min_stake: Option<Coin>,
+    // a pair whose winner flipped this many times within a window stops being
+    // ranked for the rest of it, ties do not count as a flip
+    pub max_alternations: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairActivity {
+    pub window_start: Timestamp,
+    pub ranked_games: u64,
+    pub last_winner: Option<Addr>,
+    pub alternations: u32,
+}
+
+// Without a config ties break streaks and no bonuses are paid
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StreakConfig {
+    pub ties_break_streak: bool,
+    pub milestones: Vec<StreakMilestone>,
+}
+
+// paid from the treasury when a player's streak reaches `streak` wins
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StreakMilestone {
+    pub streak: u64,
+    pub bonus: Coin,
+}
+
+// A season ranks the two-party games resolved while it runs. The standings
+// are filled in once it ends, best first and capped to the top players.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Season {
+    pub number: u64,
+    pub started_at: Timestamp,
+    pub ended_at: Option<Timestamp>,
+    pub standings: Vec<Standing>,
+    pub prizes: Vec<Prize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Standing {
+    pub player: Addr,
+    pub score: SeasonScore,
+    // the player's all-time stats when the season ended
+    pub stats: PlayerStats,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct SeasonScore {
+    pub wins: u64,
+    pub losses: u64,
+    pub ties: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Prize {
+    pub player: Addr,
+    pub amount: Coin,
+}
+
+// Aggregates over the direct, queued, house and hill games, updated wherever
+// those games emit their events. Forfeits are resolved games decided by a
+// timeout. Volume is the stakes put into games, fees the hill challenge fees.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct GlobalStats {
+    pub games_started: u64,
+    pub games_resolved: u64,
+    pub games_cancelled: u64,
+    pub games_forfeited: u64,
+    pub results: ResultCounts,
+    pub moves: MoveCounts,
+    pub volume: Vec<Coin>,
+    pub fees: Vec<Coin>,
+}
+
+impl GlobalStats {
+    pub fn resolved(&mut self, moves: (Option<GameMove>, Option<GameMove>), result: GameResult) {
+        self.games_resolved += 1;
+        self.results.add(result);
+        for game_move in [moves.0, moves.1].iter().flatten() {
+            self.moves.add(*game_move);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ResultCounts {
+    pub host_wins: u64,
+    pub opponent_wins: u64,
+    pub ties: u64,
+}
+
+impl ResultCounts {
+    pub fn add(&mut self, result: GameResult) {
+        match result {
+            GameResult::HostWins => self.host_wins += 1,
+            GameResult::OpponentWins => self.opponent_wins += 1,
+            GameResult::Tie => self.ties += 1,
+        }
+    }
+}
+
+// wins_a counts the wins of the lower address of the pair, whoever hosted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct HeadToHead {
+    pub wins_a: u64,
+    pub wins_b: u64,
+    pub ties: u64,
+    pub last_game: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MoveCounts {
+    pub rock: u64,
+    pub paper: u64,
+    pub scissors: u64,
+}
+
+impl MoveCounts {
+    pub fn add(&mut self, game_move: GameMove) {
+        match game_move {
+            GameMove::Rock => self.rock += 1,
+            GameMove::Paper => self.paper += 1,
+            GameMove::Scissors => self.scissors += 1,
+        }
+    }
+}
+
+// The cw20 this contract mints rewards on. The rate in effect is the last
+// entry of the schedule that has started.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardConfig {
+    pub token: Addr,
+    pub daily_cap: Uint128,
+    pub schedule: Vec<EmissionRate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmissionRate {
+    pub start: Timestamp,
+    pub per_game: Uint128,
+    pub per_win: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct DailyRewards {
+    // days since the unix epoch
+    pub day: u64,
+    pub minted: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HillConfig {
+    pub challenge_fee: Coin,
+    pub defenses_to_cash_out: u32,
+    pub cash_out_after: u64,
+}
+
+// The king-of-the-hill seat. The champion defends with a committed move, every
+// challenge fee goes into the pot the champion can eventually cash out.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Hill {
+    pub champion: Option<Addr>,
+    pub champion_commitment: Option<Binary>,
+    // when the champion first committed a move and became challengeable
+    pub crowned_at: Timestamp,
+    pub defenses: u32,
+    pub pot: Uint128,
+    pub challenge: Option<HillChallenge>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HillChallenge {
+    pub challenger: Addr,
+    pub challenger_move: GameMove,
+    pub expires: Expiration,
+}
+
+// a hot key allowed to start and answer games for the player until it expires
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorGrant {
+    pub operator: Addr,
+    pub expires: Expiration,
+    pub max_wager: Option<Coin>,
+}
+
+// Two players escrow the same deposit and play off-chain, only the latest
+// co-signed tally (plus any disputed round on top of it) is settled here.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Channel {
+    pub id: u64,
+    pub player_a: Addr,
+    pub pubkey_a: Binary,
+    pub player_b: Addr,
+    pub pubkey_b: Option<Binary>,
+    pub deposit: Coin,
+    pub status: ChannelStatus,
+    pub nonce: u64,
+    pub balance_a: Uint128,
+    pub balance_b: Uint128,
+    pub closes: Option<Expiration>,
+    #[serde(default)]
+    pub dispute: Option<ChannelDispute>,
+}
+
+// the round after the settled tally, opened with both signed commitments
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChannelDispute {
+    pub stake: Uint128,
+    pub commitment_a: Binary,
+    pub commitment_b: Binary,
+    pub move_a: Option<GameMove>,
+    pub move_b: Option<GameMove>,
+    pub reveal_expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelStatus {
+    Opening,
+    Open,
+    Closing,
+    Closed,
+}
+
+// A game between players on two chains. The host chain holds the deciding
+// copy, each side only escrows and pays out the stake of its own player.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IbcGame {
+    pub channel_id: String,
+    pub game_id: u64,
+    pub role: IbcRole,
+    pub host: String,
+    pub opponent: String,
+    pub host_commitment: Binary,
+    pub host_move: Option<GameMove>,
+    pub opp_move: Option<GameMove>,
+    // escrowed on this chain for the local player
+    pub stake: Option<Coin>,
+    // what the opponent escrows on its own chain
+    pub opponent_stake: Option<Coin>,
+    // address on this chain the local stake goes to if the local player loses
+    pub payout: Option<String>,
+    pub status: IbcGameStatus,
+    pub reveal_expires: Option<Expiration>,
+    // the opponent chain takes no move after this time
+    #[serde(default)]
+    pub respond_by: Option<Timestamp>,
+}
+
+// which side of the game the local player is on
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcRole {
+    Host,
+    Opponent,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcGameStatus {
+    Challenged,
+    Moved,
+}